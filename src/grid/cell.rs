@@ -1,6 +1,6 @@
 pub use gust::traits::NodeID;
 
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct GridCoords {
   pub col_index: usize,
   pub row_index: usize
@@ -0,0 +1,45 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum GridError {
+  // reading or writing a file failed
+  Io(io::Error),
+  // the image or svg for a `width` by `height` grid at `cell_size` is too
+  // large, its size doesn't fit in a `u32` or its pixels won't fit in memory
+  CanvasTooLarge {
+    width: usize,
+    height: usize,
+    cell_size: u32,
+  },
+  // the path's extension isn't an image format that can be written
+  UnsupportedExtension(String),
+}
+
+impl fmt::Display for GridError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      GridError::Io(e) => write!(f, "io error: {}", e),
+      GridError::CanvasTooLarge { width, height, cell_size } => {
+        write!(f, "a {}x{} grid is too large to draw with {}px cells", width, height, cell_size)
+      }
+      GridError::UnsupportedExtension(extension) => write!(f, "can't write images with extension {:?}", extension),
+    }
+  }
+}
+
+impl Error for GridError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      GridError::Io(e) => Some(e),
+      _ => None
+    }
+  }
+}
+
+impl From<io::Error> for GridError {
+  fn from(e: io::Error) -> GridError {
+    GridError::Io(e)
+  }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use gust::Graph;
+use rand::Rng;
+use super::cell::NodeID;
+use super::error::GridError;
+use super::img::*;
+use super::links::impl_links;
+use super::topology::impl_topology;
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Coords3D {
+  pub level: usize,
+  pub row_index: usize,
+  pub col_index: usize
+}
+
+#[derive(Debug, NodeID, PartialEq)]
+#[gust(node_id=Coords3D)]
+pub struct Cell3D {
+  coords: Coords3D
+}
+
+impl Cell3D {
+  pub fn new(coords: &Coords3D) -> Cell3D {
+    Cell3D {
+      coords: *coords,
+    }
+  }
+
+  pub fn coords(&self) -> &Coords3D {
+    &self.coords
+  }
+}
+
+#[derive(Debug)]
+pub struct Neighbors3D<'a> {
+  pub north: Option<&'a Cell3D>,
+  pub east: Option<&'a Cell3D>,
+  pub south: Option<&'a Cell3D>,
+  pub west: Option<&'a Cell3D>,
+  pub up: Option<&'a Cell3D>,
+  pub down: Option<&'a Cell3D>
+}
+
+impl<'a> Neighbors3D<'a> {
+  pub fn to_vec(&self) -> Vec<&'a Cell3D> {
+    vec![self.north, self.east, self.south, self.west, self.up, self.down].into_iter().flatten().collect()
+  }
+}
+
+// a stack of `Grid`-like levels, level 0 at the bottom. each cell sits
+// directly under the cell with the same row and column on the next level up
+#[derive(Debug)]
+pub struct Grid3D {
+  levels: usize,
+  height: usize,
+  width: usize,
+  cells: HashMap<Coords3D, Cell3D>,
+  graph: Graph<Cell3D>,
+  stair_cost: u32,
+}
+
+impl Grid3D {
+  pub fn new(levels: usize, height: usize, width: usize) -> Grid3D {
+    let mut grid = Grid3D {
+      levels,
+      height,
+      width,
+      cells: HashMap::new(),
+      graph: Graph::new(),
+      stair_cost: 1,
+    };
+    for coords in grid.coords() {
+      let cell = Cell3D::new(&coords);
+      grid.cells.insert(coords, cell);
+    }
+    grid
+  }
+
+  pub fn levels(&self) -> usize {
+    self.levels
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn cells(&self) -> &HashMap<Coords3D, Cell3D> {
+    &self.cells
+  }
+
+  pub fn cell_at(&self, coords: &Coords3D) -> Option<&Cell3D> {
+    self.cells.get(coords)
+  }
+
+  pub fn coords(&self) -> Vec<Coords3D> {
+    let mut coords = Vec::new();
+    for level in 0..self.levels {
+      for col_index in 0..self.width {
+        for row_index in 0..self.height {
+          coords.push(Coords3D {
+            level,
+            row_index,
+            col_index
+          });
+        }
+      }
+    }
+    coords
+  }
+
+  pub fn rand_cell(&self) -> &Cell3D {
+    self.rand_cell_with_rng(&mut rand::thread_rng())
+  }
+
+  pub fn rand_cell_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> &Cell3D {
+    let level = rng.gen_range(0, self.levels);
+    let row_index = rng.gen_range(0, self.height);
+    let col_index = rng.gen_range(0, self.width);
+    self.cell_at(&Coords3D {
+      level,
+      row_index,
+      col_index
+    }).unwrap()
+  }
+
+  pub fn north(&self, cell: &Cell3D) -> Option<&Cell3D> {
+    let coords = cell.coords();
+    coords.row_index.checked_add(1).and_then(|row_index| self.cell_at(&Coords3D {
+      row_index,
+      ..*coords
+    }))
+  }
+
+  pub fn east(&self, cell: &Cell3D) -> Option<&Cell3D> {
+    let coords = cell.coords();
+    coords.col_index.checked_add(1).and_then(|col_index| self.cell_at(&Coords3D {
+      col_index,
+      ..*coords
+    }))
+  }
+
+  pub fn south(&self, cell: &Cell3D) -> Option<&Cell3D> {
+    let coords = cell.coords();
+    coords.row_index.checked_sub(1).and_then(|row_index| self.cell_at(&Coords3D {
+      row_index,
+      ..*coords
+    }))
+  }
+
+  pub fn west(&self, cell: &Cell3D) -> Option<&Cell3D> {
+    let coords = cell.coords();
+    coords.col_index.checked_sub(1).and_then(|col_index| self.cell_at(&Coords3D {
+      col_index,
+      ..*coords
+    }))
+  }
+
+  pub fn up(&self, cell: &Cell3D) -> Option<&Cell3D> {
+    let coords = cell.coords();
+    coords.level.checked_add(1).and_then(|level| self.cell_at(&Coords3D {
+      level,
+      ..*coords
+    }))
+  }
+
+  pub fn down(&self, cell: &Cell3D) -> Option<&Cell3D> {
+    let coords = cell.coords();
+    coords.level.checked_sub(1).and_then(|level| self.cell_at(&Coords3D {
+      level,
+      ..*coords
+    }))
+  }
+
+  pub fn neighbors(&self, cell: &Cell3D) -> Neighbors3D<'_> {
+    Neighbors3D {
+      north: self.north(cell),
+      east: self.east(cell),
+      south: self.south(cell),
+      west: self.west(cell),
+      up: self.up(cell),
+      down: self.down(cell),
+    }
+  }
+
+  // what it costs to take the stairs between levels, 1 unless it's been set.
+  // making stairs dear gets `Dijkstra` to find the path with the fewest
+  // stairs rather than the fewest steps
+  pub fn stair_cost(&self) -> u32 {
+    self.stair_cost
+  }
+
+  pub fn set_stair_cost(&mut self, cost: u32) {
+    self.stair_cost = cost;
+  }
+
+  // the cost of stepping from `source` into `destination`, the stair cost
+  // going up or down and 1 on the level
+  pub fn cost(&self, source: &Coords3D, destination: &Coords3D) -> u32 {
+    if source.level == destination.level {
+      1
+    } else {
+      self.stair_cost
+    }
+  }
+
+  // how many times a path, like the ones `Dijkstra` finds, takes the stairs
+  pub fn stair_moves(path: &[Coords3D]) -> usize {
+    path.windows(2).filter(|step| step[0].level != step[1].level).count()
+  }
+
+  pub fn to_img(&self, path: &str, cell_size: u32) -> GridImage {
+    match self.try_to_img(path, cell_size) {
+      Ok(grid_image) => grid_image,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  // like `to_img` but returns an error rather than panicking, nothing is
+  // drawn if the path's extension can't be written
+  pub fn try_to_img(&self, path: &str, cell_size: u32) -> Result<GridImage, GridError> {
+    check_extension(path.as_ref())?;
+    let grid_image = try_grid3d_to_img(self, cell_size)?;
+    grid_image.save(path)?;
+    Ok(grid_image)
+  }
+}
+
+impl_links!(Grid3D, Cell3D, Coords3D, walls);
+
+impl_topology!(Grid3D, Coords3D, weighted);
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fmt;
+use gust::Graph;
+use rand::Rng;
+use super::cell::*;
+use super::error::GridError;
+use super::img::*;
+use super::links::impl_links;
+use super::topology::impl_topology;
+
+#[derive(Debug)]
+pub struct HexNeighbors<'a> {
+  pub north: Option<&'a Cell>,
+  pub northeast: Option<&'a Cell>,
+  pub southeast: Option<&'a Cell>,
+  pub south: Option<&'a Cell>,
+  pub southwest: Option<&'a Cell>,
+  pub northwest: Option<&'a Cell>
+}
+
+impl<'a> HexNeighbors<'a> {
+  pub fn to_vec(&self) -> Vec<&'a Cell> {
+    vec![self.north, self.northeast, self.southeast, self.south, self.southwest, self.northwest].into_iter().flatten().collect()
+  }
+}
+
+// flat-topped hexagons in offset columns. rows count up from the bottom like
+// `Grid`, and odd columns sit half a cell further north than even columns
+#[derive(Debug)]
+pub struct HexGrid {
+  height: usize,
+  width: usize,
+  cells: HashMap<GridCoords, Cell>,
+  graph: Graph<Cell>,
+}
+
+impl HexGrid {
+  pub fn new(height: usize, width: usize) -> HexGrid {
+    let mut grid = HexGrid {
+      height,
+      width,
+      cells: HashMap::new(),
+      graph: Graph::new(),
+    };
+    for coords in grid.coords() {
+      let cell = Cell::new(&coords);
+      grid.cells.insert(coords, cell);
+    }
+    grid
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn cells(&self) -> &HashMap<GridCoords, Cell> {
+    &self.cells
+  }
+
+  pub fn cell_at(&self, coords: &GridCoords) -> Option<&Cell> {
+    self.cells.get(coords)
+  }
+
+  pub fn coords(&self) -> Vec<GridCoords> {
+    let mut coords = Vec::new();
+    for col_index in 0..self.width {
+      for row_index in 0..self.height {
+        coords.push(GridCoords {
+          col_index,
+          row_index
+        });
+      }
+    }
+    coords
+  }
+
+  pub fn rand_cell(&self) -> &Cell {
+    self.rand_cell_with_rng(&mut rand::thread_rng())
+  }
+
+  pub fn rand_cell_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> &Cell {
+    let row_index = rng.gen_range(0, self.height);
+    let col_index = rng.gen_range(0, self.width);
+    self.cell_at(&GridCoords {
+      col_index,
+      row_index
+    }).unwrap()
+  }
+
+  // the cell offset from `cell` by whole rows and columns, if it's on the grid
+  fn offset(&self, cell: &Cell, rows: isize, cols: isize) -> Option<&Cell> {
+    let coords = cell.coords();
+    let row_index = coords.row_index as isize + rows;
+    let col_index = coords.col_index as isize + cols;
+    if row_index < 0 || col_index < 0 {
+      return None;
+    }
+    self.cell_at(&GridCoords {
+      col_index: col_index as usize,
+      row_index: row_index as usize
+    })
+  }
+
+  // how far the diagonal neighbors are shifted north of this cell's row
+  fn diagonal_rows(&self, cell: &Cell) -> (isize, isize) {
+    if cell.coords().col_index % 2 == 0 {
+      (0, -1)
+    } else {
+      (1, 0)
+    }
+  }
+
+  pub fn north(&self, cell: &Cell) -> Option<&Cell> {
+    self.offset(cell, 1, 0)
+  }
+
+  pub fn south(&self, cell: &Cell) -> Option<&Cell> {
+    self.offset(cell, -1, 0)
+  }
+
+  pub fn northeast(&self, cell: &Cell) -> Option<&Cell> {
+    self.offset(cell, self.diagonal_rows(cell).0, 1)
+  }
+
+  pub fn southeast(&self, cell: &Cell) -> Option<&Cell> {
+    self.offset(cell, self.diagonal_rows(cell).1, 1)
+  }
+
+  pub fn northwest(&self, cell: &Cell) -> Option<&Cell> {
+    self.offset(cell, self.diagonal_rows(cell).0, -1)
+  }
+
+  pub fn southwest(&self, cell: &Cell) -> Option<&Cell> {
+    self.offset(cell, self.diagonal_rows(cell).1, -1)
+  }
+
+  pub fn neighbors(&self, cell: &Cell) -> HexNeighbors<'_> {
+    HexNeighbors {
+      north: self.north(cell),
+      northeast: self.northeast(cell),
+      southeast: self.southeast(cell),
+      south: self.south(cell),
+      southwest: self.southwest(cell),
+      northwest: self.northwest(cell),
+    }
+  }
+
+  pub fn to_img(&self, path: &str, cell_size: u32) -> GridImage {
+    match self.try_to_img(path, cell_size) {
+      Ok(grid_image) => grid_image,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  // like `to_img` but returns an error rather than panicking, nothing is
+  // drawn if the path's extension can't be written
+  pub fn try_to_img(&self, path: &str, cell_size: u32) -> Result<GridImage, GridError> {
+    check_extension(path.as_ref())?;
+    let grid_image = try_hex_to_img(self, cell_size)?;
+    grid_image.save(path)?;
+    Ok(grid_image)
+  }
+}
+
+impl_links!(HexGrid, Cell, GridCoords, walls);
+
+impl_topology!(HexGrid);
+
+// each hex is drawn as
+//  __
+// /  \
+// \__/
+// with neighboring hexes sharing edges
+impl fmt::Display for HexGrid {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let line_count = self.height * 2 + 2;
+    let line_width = self.width * 3 + 1;
+    let mut lines = vec![vec![' '; line_width]; line_count];
+
+    for coords in self.coords() {
+      let cell = self.cell_at(&coords).unwrap();
+      let x = coords.col_index * 3;
+      // text runs top down, and even columns sit half a cell lower
+      let mut y = (self.height - 1 - coords.row_index) * 2;
+      if coords.col_index % 2 == 0 {
+        y += 1;
+      }
+
+      if self.has_wall(cell, self.north(cell)) {
+        lines[y][x + 1] = '_';
+        lines[y][x + 2] = '_';
+      }
+      if self.has_wall(cell, self.northwest(cell)) {
+        lines[y + 1][x] = '/';
+      }
+      if self.has_wall(cell, self.northeast(cell)) {
+        lines[y + 1][x + 3] = '\\';
+      }
+      if self.has_wall(cell, self.southwest(cell)) {
+        lines[y + 2][x] = '\\';
+      }
+      if self.has_wall(cell, self.south(cell)) {
+        lines[y + 2][x + 1] = '_';
+        lines[y + 2][x + 2] = '_';
+      }
+      if self.has_wall(cell, self.southeast(cell)) {
+        lines[y + 2][x + 3] = '/';
+      }
+    }
+
+    for line in lines {
+      let line: String = line.into_iter().collect();
+      writeln!(f, "{}", line.trim_end())?;
+    }
+
+    Ok(())
+  }
+}
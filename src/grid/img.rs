@@ -1,5 +1,14 @@
 use std::collections::*;
+use std::f64::consts::PI;
+use std::io;
+use std::path::Path;
 use super::{Grid, GridCoords};
+use super::error::GridError;
+use super::grid3d::Grid3D;
+use super::hex::HexGrid;
+use super::polar::PolarGrid;
+use super::triangle::TriangleGrid;
+use super::weave::{WeaveGrid, Passage};
 use std::convert::{TryInto, TryFrom};
 use line_rs::*;
 
@@ -7,11 +16,191 @@ pub struct GridImage {
   pub canvas: image::RgbImage,
   pub cell_size: u32,
   pub padding: u32,
+  // what it was drawn with, anything drawn on top follows it too
+  pub style: RenderStyle,
+}
+
+// how the renderers draw a grid, defaults to thin black walls on white with
+// a light green solution, e.g.
+// `RenderStyle::new().with_wall_thickness(4).with_padding(10)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderStyle {
+  wall_color: image::Rgb<u8>,
+  wall_thickness: u32,
+  background: image::Rgb<u8>,
+  padding: u32,
+  solution_color: image::Rgb<u8>,
+  solution_width: u32,
+  start_marker: Option<image::Rgb<u8>>,
+  end_marker: Option<image::Rgb<u8>>,
+}
+
+impl Default for RenderStyle {
+  fn default() -> RenderStyle {
+    RenderStyle {
+      wall_color: image::Rgb { data: [0, 0, 0] },
+      wall_thickness: 1,
+      background: image::Rgb { data: [255, 255, 255] },
+      padding: 5,
+      solution_color: image::Rgb { data: [120, 255, 120] },
+      solution_width: 1,
+      start_marker: None,
+      end_marker: None,
+    }
+  }
+}
+
+impl RenderStyle {
+  pub fn new() -> RenderStyle {
+    RenderStyle::default()
+  }
+
+  pub fn with_wall_color(mut self, color: image::Rgb<u8>) -> RenderStyle {
+    self.wall_color = color;
+    self
+  }
+
+  // walls are centered on the cell edges, so padding should be at least
+  // half as wide to keep the outer walls whole
+  pub fn with_wall_thickness(mut self, thickness: u32) -> RenderStyle {
+    self.wall_thickness = thickness.max(1);
+    self
+  }
+
+  pub fn with_background(mut self, color: image::Rgb<u8>) -> RenderStyle {
+    self.background = color;
+    self
+  }
+
+  pub fn with_padding(mut self, padding: u32) -> RenderStyle {
+    self.padding = padding;
+    self
+  }
+
+  pub fn with_solution_color(mut self, color: image::Rgb<u8>) -> RenderStyle {
+    self.solution_color = color;
+    self
+  }
+
+  pub fn with_solution_width(mut self, width: u32) -> RenderStyle {
+    self.solution_width = width.max(1);
+    self
+  }
+
+  // marks the first cell of a solution with a square of `color`
+  pub fn with_start_marker(mut self, color: image::Rgb<u8>) -> RenderStyle {
+    self.start_marker = Some(color);
+    self
+  }
+
+  // marks the last cell of a solution with a square of `color`
+  pub fn with_end_marker(mut self, color: image::Rgb<u8>) -> RenderStyle {
+    self.end_marker = Some(color);
+    self
+  }
+
+  pub fn wall_color(&self) -> image::Rgb<u8> {
+    self.wall_color
+  }
+
+  pub fn wall_thickness(&self) -> u32 {
+    self.wall_thickness
+  }
+
+  pub fn background(&self) -> image::Rgb<u8> {
+    self.background
+  }
+
+  pub fn padding(&self) -> u32 {
+    self.padding
+  }
+
+  pub fn solution_color(&self) -> image::Rgb<u8> {
+    self.solution_color
+  }
+
+  pub fn solution_width(&self) -> u32 {
+    self.solution_width
+  }
+
+  pub fn start_marker(&self) -> Option<image::Rgb<u8>> {
+    self.start_marker
+  }
+
+  pub fn end_marker(&self) -> Option<image::Rgb<u8>> {
+    self.end_marker
+  }
+}
+
+// the extensions `image` can write to
+const EXTENSIONS: [&str; 9] = ["png", "jpg", "jpeg", "bmp", "ico", "pbm", "pgm", "ppm", "pam"];
+
+// checks the extension before anything is written, `image` creates the file
+// before finding out it can't write that format
+pub(crate) fn check_extension(path: &Path) -> Result<(), GridError> {
+  let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("").to_ascii_lowercase();
+  if EXTENSIONS.contains(&extension.as_str()) {
+    Ok(())
+  } else {
+    Err(GridError::UnsupportedExtension(extension))
+  }
+}
+
+impl GridImage {
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GridError> {
+    check_extension(path.as_ref())?;
+    self.canvas.save(path)?;
+    Ok(())
+  }
+
+  pub fn write_png<W: io::Write>(&self, writer: W) -> Result<(), GridError> {
+    let encoder = image::png::PNGEncoder::new(writer);
+    encoder.encode(&self.canvas, self.canvas.width(), self.canvas.height(), image::ColorType::RGB(8))?;
+    Ok(())
+  }
+}
+
+// the size in pixels of a canvas holding `width` by `height` cells, or an
+// error if it doesn't fit in a `u32` or the buffer wouldn't fit in memory
+fn canvas_size(width: usize, height: usize, cell_size: u32, padding: u32) -> Result<(u32, u32), GridError> {
+  let side = |cells: usize| u32::try_from(cells).ok().and_then(|cells| cells.checked_mul(cell_size));
+  drawing_canvas_size(width, height, cell_size, (side(width), side(height)), padding)
+}
+
+// like `canvas_size`, for grids that measure their drawing in pixels
+// themselves. a side is `None` if it already overflowed
+fn drawing_canvas_size(width: usize, height: usize, cell_size: u32, drawing: (Option<u32>, Option<u32>), padding: u32) -> Result<(u32, u32), GridError> {
+  let too_large = || GridError::CanvasTooLarge {
+    width,
+    height,
+    cell_size,
+  };
+  let padding_total = padding.checked_mul(2).ok_or_else(too_large)?;
+  let side = |pixels: Option<u32>| pixels.and_then(|pixels| pixels.checked_add(padding_total));
+  let canvas_width = side(drawing.0).ok_or_else(too_large)?;
+  let canvas_height = side(drawing.1).ok_or_else(too_large)?;
+  // 3 bytes a pixel
+  usize::try_from(canvas_width).ok()
+    .and_then(|canvas_width| usize::try_from(canvas_height).ok()?.checked_mul(canvas_width))
+    .and_then(|pixels| pixels.checked_mul(3))
+    .filter(|bytes| *bytes <= isize::max_value() as usize)
+    .ok_or_else(too_large)?;
+  Ok((canvas_width, canvas_height))
+}
+
+// `pixels` rounded up, if it fits in a `u32`
+fn whole_pixels(pixels: f64) -> Option<u32> {
+  let pixels = pixels.ceil();
+  if pixels <= f64::from(u32::max_value()) {
+    Some(pixels as u32)
+  } else {
+    None
+  }
 }
 
 enum CellPoint {
   TopLeft,
-  // TopRight,
+  TopRight,
   BottomRight,
   Center
 }
@@ -31,35 +220,67 @@ fn get_point(origin: (u32, u32), cell_size: u32, point: CellPoint) -> (u32, u32)
     CellPoint::BottomRight => {
       (origin.0 + cell_size, origin.1)
     }
-    // CellPoint::TopRight => {
-    //   (origin.0 + cell_size, origin.1 + cell_size)
-    // }
+    CellPoint::TopRight => {
+      (origin.0 + cell_size, origin.1 + cell_size)
+    }
     CellPoint::Center => {
       (origin.0 + (cell_size / 2), origin.1 + (cell_size / 2))
     }
   }
 }
 
-fn draw_line(mut canvas: image::RgbImage, color: image::Rgb<u8>, (x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> image::RgbImage {
+// a square brush `thickness` pixels across centered on the point, anything
+// off the edge of the canvas is dropped
+fn draw_point(canvas: &mut image::RgbImage, color: image::Rgb<u8>, thickness: u32, (x, y): (u32, u32)) {
+  let (left, bottom) = (x.saturating_sub((thickness - 1) / 2), y.saturating_sub((thickness - 1) / 2));
+  for px_x in left..left.saturating_add(thickness).min(canvas.width()) {
+    for px_y in bottom..bottom.saturating_add(thickness).min(canvas.height()) {
+      canvas.put_pixel(px_x, px_y, color);
+    }
+  }
+}
+
+fn draw_line(mut canvas: image::RgbImage, color: image::Rgb<u8>, thickness: u32, (x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> image::RgbImage {
   let p1 = bresenham::Point::new(x1, y1);
   let p2 = bresenham::Point::new(x2, y2);
   let line = bresenham::calculate_line(p1, p2);
   for point in line {
-    canvas.put_pixel(point.x, point.y, color);
+    draw_point(&mut canvas, color, thickness, (point.x, point.y));
   };
   canvas
 }
 
+fn polar_point(center: f64, radius: f64, theta: f64) -> (u32, u32) {
+  ((center + radius * theta.cos()).round() as u32, (center + radius * theta.sin()).round() as u32)
+}
+
+// approximates the arc with straight segments roughly 2px long
+fn draw_arc(mut canvas: image::RgbImage, color: image::Rgb<u8>, thickness: u32, center: f64, radius: f64, from_theta: f64, to_theta: f64) -> image::RgbImage {
+  let segments = ((radius * (to_theta - from_theta)) / 2.0).ceil().max(1.0) as u32;
+  let step = (to_theta - from_theta) / f64::from(segments);
+  let mut trailing_point = polar_point(center, radius, from_theta);
+  for segment in 1..=segments {
+    let point = polar_point(center, radius, from_theta + step * f64::from(segment));
+    canvas = draw_line(canvas, color, thickness, trailing_point, point);
+    trailing_point = point;
+  }
+  canvas
+}
+
 fn fill_square(mut canvas: image::RgbImage, color: image::Rgb<u8>, origin: (u32, u32), cell_size: u32) -> image::RgbImage {
-  for px_x in 0..cell_size {
-    for px_y in 0..cell_size {
-      canvas.put_pixel(origin.0 + px_x, origin.1 + px_y, color);
+  for px_x in origin.0..(origin.0 + cell_size).min(canvas.width()) {
+    for px_y in origin.1..(origin.1 + cell_size).min(canvas.height()) {
+      canvas.put_pixel(px_x, px_y, color);
     }
   }
   canvas
 }
 
-fn modify_color_by_distance(max_distance: u32, distance: u32, color: image::Rgb<u8>) -> image::Rgb<u8> {
+pub(crate) fn modify_color_by_distance(max_distance: u32, distance: u32, color: image::Rgb<u8>) -> image::Rgb<u8> {
+  // a lone cell is as far as anything gets
+  if max_distance == 0 {
+    return color;
+  }
   let fraction = (f64::try_from(distance).unwrap() / f64::try_from(max_distance).unwrap()) as f32;
   let fraction = 1.0 - fraction;
   let [r, g, b] = color.data;
@@ -73,85 +294,476 @@ fn modify_color_by_distance(max_distance: u32, distance: u32, color: image::Rgb<
 }
 
 pub fn to_img(grid: &Grid, cell_size: u32) -> GridImage {
-  let padding_px = 5;
-  let padding_total = padding_px * 2;
+  to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
 
-  let grid_width_u32;
-  if let Ok(width_u32) = u32::try_from(grid.width) {
-    grid_width_u32 = width_u32
-  } else {
-    panic!("Grid width is too large to convert into an image (u32 max)")
+pub fn to_img_with_style(grid: &Grid, cell_size: u32, style: &RenderStyle) -> GridImage {
+  match try_to_img_with_style(grid, cell_size, style) {
+    Ok(grid_image) => grid_image,
+    Err(e) => panic!("{}", e)
   }
+}
 
-  let grid_height_u32;
-  if let Ok(height_u32) = u32::try_from(grid.width) {
-    grid_height_u32 = height_u32
-  } else {
-    panic!("Grid height is too large to convert into an image (u32 max)")
+pub fn try_to_img(grid: &Grid, cell_size: u32) -> Result<GridImage, GridError> {
+  try_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn try_to_img_with_style(grid: &Grid, cell_size: u32, style: &RenderStyle) -> Result<GridImage, GridError> {
+  let padding_px = style.padding;
+  let (grid_width, grid_height) = canvas_size(grid.width, grid.height, cell_size, padding_px)?;
+
+  let wall_color = style.wall_color;
+  let wall_thickness = style.wall_thickness;
+
+  let mut canvas: image::RgbImage = image::ImageBuffer::from_pixel(grid_width, grid_height, style.background);
+  for row_index in 0..grid.height {
+    for col_index in 0..grid.width {
+      let coords = GridCoords {
+        col_index,
+        row_index
+      };
+      let west = grid.west_of(&coords);
+      let south = grid.south_of(&coords);
+
+      let origin = get_origin(padding_px, cell_size, &coords);
+      let top_left = get_point(origin, cell_size, CellPoint::TopLeft);
+      let top_right = get_point(origin, cell_size, CellPoint::TopRight);
+      let bottom_right = get_point(origin, cell_size, CellPoint::BottomRight);
+
+      if grid.has_wall(&coords, west.as_ref()) {
+        canvas = draw_line(canvas, wall_color, wall_thickness, origin, top_left);
+      }
+
+      if grid.has_wall(&coords, south.as_ref()) {
+        canvas = draw_line(canvas, wall_color, wall_thickness, origin, bottom_right);
+      }
+
+      // the outer edges along the top and right of the grid, open where
+      // they wrap round and the cells either side are linked
+      if row_index + 1 == grid.height && grid.has_wall(&coords, grid.north_of(&coords).as_ref()) {
+        canvas = draw_line(canvas, wall_color, wall_thickness, top_left, top_right);
+      }
+      if col_index + 1 == grid.width && grid.has_wall(&coords, grid.east_of(&coords).as_ref()) {
+        canvas = draw_line(canvas, wall_color, wall_thickness, bottom_right, top_right);
+      }
+    }
+  }
+
+  canvas = image::imageops::flip_vertical(&canvas);
+
+  Ok(GridImage {
+    canvas,
+    cell_size,
+    padding: padding_px,
+    style: style.clone(),
+  })
+}
+
+// each ring is `cell_size` thick, inward walls are drawn as arcs and
+// clockwise walls as radial lines
+pub fn polar_to_img(grid: &PolarGrid, cell_size: u32) -> GridImage {
+  polar_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn polar_to_img_with_style(grid: &PolarGrid, cell_size: u32, style: &RenderStyle) -> GridImage {
+  match try_polar_to_img_with_style(grid, cell_size, style) {
+    Ok(grid_image) => grid_image,
+    Err(e) => panic!("{}", e)
   }
+}
+
+pub fn try_polar_to_img(grid: &PolarGrid, cell_size: u32) -> Result<GridImage, GridError> {
+  try_polar_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
 
-  let grid_width = (grid_width_u32 * cell_size) + padding_total;
-  let grid_height = (grid_height_u32 * cell_size) + padding_total;
+pub fn try_polar_to_img_with_style(grid: &PolarGrid, cell_size: u32, style: &RenderStyle) -> Result<GridImage, GridError> {
+  let padding_px = style.padding;
 
-  let white = image::Rgb { data: [255, 255, 255] };
-  let black = image::Rgb { data: [0, 0, 0] };
+  // a ring's width either side of the center for every ring
+  let across = grid.height().saturating_mul(2);
+  let (img_size, _) = canvas_size(across, across, cell_size, padding_px)?;
+  let outer_radius_px = img_size / 2 - padding_px;
+  let center = f64::from(img_size / 2);
 
-  let mut canvas: image::RgbImage = image::ImageBuffer::from_pixel(grid_width, grid_height, white);
-  for (_, cell) in grid.cells.iter() {
-    let neighbors = grid.neighbors(cell);
-    let links = grid.links(cell);
+  let wall_color = style.wall_color;
+  let wall_thickness = style.wall_thickness;
 
-    let coords = cell.coords();
-    let origin = get_origin(padding_px, cell_size, coords);
+  let mut canvas: image::RgbImage = image::ImageBuffer::from_pixel(img_size, img_size, style.background);
+  for coords in grid.coords() {
+    if coords.row_index == 0 {
+      // the center cell has no walls of its own
+      continue;
+    }
+    let cell = grid.cell_at(&coords).unwrap();
+    let theta = 2.0 * PI / grid.row_length(coords.row_index) as f64;
+    let inner_radius = f64::from(cell_size) * coords.row_index as f64;
+    let outer_radius = inner_radius + f64::from(cell_size);
+    let theta_ccw = theta * coords.col_index as f64;
+    let theta_cw = theta_ccw + theta;
+
+    match grid.inward(cell) {
+      Some(inward) if grid.is_linked(&coords, inward.coords()) => {},
+      _ => {
+        canvas = draw_arc(canvas, wall_color, wall_thickness, center, inner_radius, theta_ccw, theta_cw);
+      }
+    }
+
+    match grid.cw(cell) {
+      Some(cw) if grid.is_linked(&coords, cw.coords()) => {},
+      _ => {
+        let inner = polar_point(center, inner_radius, theta_cw);
+        let outer = polar_point(center, outer_radius, theta_cw);
+        canvas = draw_line(canvas, wall_color, wall_thickness, inner, outer);
+      }
+    }
+  }
+
+  let outer_radius = f64::from(outer_radius_px);
+  canvas = draw_arc(canvas, wall_color, wall_thickness, center, outer_radius, 0.0, 2.0 * PI);
+
+  Ok(GridImage {
+    canvas,
+    cell_size,
+    padding: padding_px,
+    style: style.clone(),
+  })
+}
+
+fn round_point((x, y): (f64, f64)) -> (u32, u32) {
+  (x.round() as u32, y.round() as u32)
+}
+
+// flat-topped hexagons, `cell_size` is the distance from a hex's center to
+// each of its corners
+pub fn hex_to_img(grid: &HexGrid, cell_size: u32) -> GridImage {
+  hex_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn hex_to_img_with_style(grid: &HexGrid, cell_size: u32, style: &RenderStyle) -> GridImage {
+  match try_hex_to_img_with_style(grid, cell_size, style) {
+    Ok(grid_image) => grid_image,
+    Err(e) => panic!("{}", e)
+  }
+}
+
+pub fn try_hex_to_img(grid: &HexGrid, cell_size: u32) -> Result<GridImage, GridError> {
+  try_hex_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn try_hex_to_img_with_style(grid: &HexGrid, cell_size: u32, style: &RenderStyle) -> Result<GridImage, GridError> {
+  let padding_px = style.padding;
+
+  let size = f64::from(cell_size);
+  let a_size = size / 2.0;
+  let b_size = size * 3f64.sqrt() / 2.0;
+  let hex_height = b_size * 2.0;
+
+  let drawing = (
+    whole_pixels(3.0 * a_size * grid.width() as f64 + a_size),
+    whole_pixels(hex_height * grid.height() as f64 + b_size),
+  );
+  let (img_width, img_height) = drawing_canvas_size(grid.width(), grid.height(), cell_size, drawing, padding_px)?;
+
+  let wall_color = style.wall_color;
+  let wall_thickness = style.wall_thickness;
+
+  let mut canvas: image::RgbImage = image::ImageBuffer::from_pixel(img_width, img_height, style.background);
+  for coords in grid.coords() {
+    let cell = grid.cell_at(&coords).unwrap();
+    let center_x = f64::from(padding_px) + size + 3.0 * a_size * coords.col_index as f64;
+    // image rows run top down, and even columns sit half a cell lower
+    let mut center_y = f64::from(padding_px) + b_size + hex_height * (grid.height() - 1 - coords.row_index) as f64;
+    if coords.col_index % 2 == 0 {
+      center_y += b_size;
+    }
+
+    let x_far_west = center_x - size;
+    let x_near_west = center_x - a_size;
+    let x_near_east = center_x + a_size;
+    let x_far_east = center_x + size;
+    let y_north = center_y - b_size;
+    let y_south = center_y + b_size;
+
+    let walls = vec![
+      (grid.north(cell), (x_near_west, y_north), (x_near_east, y_north)),
+      (grid.northeast(cell), (x_near_east, y_north), (x_far_east, center_y)),
+      (grid.southeast(cell), (x_far_east, center_y), (x_near_east, y_south)),
+      (grid.south(cell), (x_near_east, y_south), (x_near_west, y_south)),
+      (grid.southwest(cell), (x_near_west, y_south), (x_far_west, center_y)),
+      (grid.northwest(cell), (x_far_west, center_y), (x_near_west, y_north)),
+    ];
+    for (neighbor, from, to) in walls {
+      if grid.has_wall(cell, neighbor) {
+        canvas = draw_line(canvas, wall_color, wall_thickness, round_point(from), round_point(to));
+      }
+    }
+  }
+
+  Ok(GridImage {
+    canvas,
+    cell_size,
+    padding: padding_px,
+    style: style.clone(),
+  })
+}
+
+// equilateral triangles `cell_size` wide, neighboring triangles in a row
+// overlap by half their width
+pub fn triangle_to_img(grid: &TriangleGrid, cell_size: u32) -> GridImage {
+  triangle_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn triangle_to_img_with_style(grid: &TriangleGrid, cell_size: u32, style: &RenderStyle) -> GridImage {
+  match try_triangle_to_img_with_style(grid, cell_size, style) {
+    Ok(grid_image) => grid_image,
+    Err(e) => panic!("{}", e)
+  }
+}
+
+pub fn try_triangle_to_img(grid: &TriangleGrid, cell_size: u32) -> Result<GridImage, GridError> {
+  try_triangle_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn try_triangle_to_img_with_style(grid: &TriangleGrid, cell_size: u32, style: &RenderStyle) -> Result<GridImage, GridError> {
+  let padding_px = style.padding;
+
+  let size = f64::from(cell_size);
+  let half_width = size / 2.0;
+  let tri_height = size * 3f64.sqrt() / 2.0;
+  let half_height = tri_height / 2.0;
+
+  let drawing = (
+    whole_pixels(half_width * (grid.width() as f64 + 1.0)),
+    whole_pixels(tri_height * grid.height() as f64),
+  );
+  let (img_width, img_height) = drawing_canvas_size(grid.width(), grid.height(), cell_size, drawing, padding_px)?;
+
+  let wall_color = style.wall_color;
+  let wall_thickness = style.wall_thickness;
+
+  let mut canvas: image::RgbImage = image::ImageBuffer::from_pixel(img_width, img_height, style.background);
+  for coords in grid.coords() {
+    let cell = grid.cell_at(&coords).unwrap();
+    let center_x = f64::from(padding_px) + half_width + half_width * coords.col_index as f64;
+    // image rows run top down
+    let center_y = f64::from(padding_px) + half_height + tri_height * (grid.height() - 1 - coords.row_index) as f64;
+
+    let west_x = center_x - half_width;
+    let east_x = center_x + half_width;
+    let (apex_y, base_y, base_neighbor);
+    if grid.is_upright(cell) {
+      apex_y = center_y - half_height;
+      base_y = center_y + half_height;
+      base_neighbor = grid.south(cell);
+    } else {
+      apex_y = center_y + half_height;
+      base_y = center_y - half_height;
+      base_neighbor = grid.north(cell);
+    }
+
+    let walls = vec![
+      (grid.west(cell), (west_x, base_y), (center_x, apex_y)),
+      (grid.east(cell), (east_x, base_y), (center_x, apex_y)),
+      (base_neighbor, (west_x, base_y), (east_x, base_y)),
+    ];
+    for (neighbor, from, to) in walls {
+      if grid.has_wall(cell, neighbor) {
+        canvas = draw_line(canvas, wall_color, wall_thickness, round_point(from), round_point(to));
+      }
+    }
+  }
+
+  Ok(GridImage {
+    canvas,
+    cell_size,
+    padding: padding_px,
+    style: style.clone(),
+  })
+}
+
+// levels side by side from left to right, level 0 first, with a gap of
+// one cell between them. stairs up are marked with an arrow pointing into
+// the top right corner of the cell, stairs down into the bottom left
+pub fn grid3d_to_img(grid: &Grid3D, cell_size: u32) -> GridImage {
+  grid3d_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn grid3d_to_img_with_style(grid: &Grid3D, cell_size: u32, style: &RenderStyle) -> GridImage {
+  match try_grid3d_to_img_with_style(grid, cell_size, style) {
+    Ok(grid_image) => grid_image,
+    Err(e) => panic!("{}", e)
+  }
+}
+
+pub fn try_grid3d_to_img(grid: &Grid3D, cell_size: u32) -> Result<GridImage, GridError> {
+  try_grid3d_to_img_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn try_grid3d_to_img_with_style(grid: &Grid3D, cell_size: u32, style: &RenderStyle) -> Result<GridImage, GridError> {
+  let padding_px = style.padding;
+
+  // every level plus the gaps between them
+  let across = grid.levels().saturating_mul(grid.width()).saturating_add(grid.levels().saturating_sub(1));
+  let (img_width, img_height) = canvas_size(across, grid.height(), cell_size, padding_px)?;
+  // can't overflow, the canvas is wider than a level
+  let level_width = u32::try_from(grid.width()).unwrap() * cell_size;
+  let arrow_size = cell_size / 8;
+
+  let wall_color = style.wall_color;
+  let wall_thickness = style.wall_thickness;
+
+  let mut canvas: image::RgbImage = image::ImageBuffer::from_pixel(img_width, img_height, style.background);
+  for coords in grid.coords() {
+    let cell = grid.cell_at(&coords).unwrap();
+    let level_offset = u32::try_from(coords.level).unwrap() * (level_width + cell_size);
+    let origin = get_origin(padding_px, cell_size, &GridCoords {
+      col_index: coords.col_index,
+      row_index: coords.row_index
+    });
+    let origin = (origin.0 + level_offset, origin.1);
     let top_left = get_point(origin, cell_size, CellPoint::TopLeft);
+    let top_right = get_point(origin, cell_size, CellPoint::TopRight);
     let bottom_right = get_point(origin, cell_size, CellPoint::BottomRight);
 
-    match neighbors.west {
-      Some(west) => {
-        if !links.iter().any(|linked_cell| *linked_cell == west) {
-          canvas = draw_line(canvas, black, origin, top_left);
-        }
-      },
-      None => {
-        canvas = draw_line(canvas, black, origin, top_left);
+    let walls = vec![
+      (grid.west(cell), origin, top_left),
+      (grid.south(cell), origin, bottom_right),
+      (grid.north(cell), top_left, top_right),
+      (grid.east(cell), bottom_right, top_right),
+    ];
+    for (neighbor, from, to) in walls {
+      if grid.has_wall(cell, neighbor) {
+        canvas = draw_line(canvas, wall_color, wall_thickness, from, to);
       }
     }
 
-    match neighbors.south {
-      Some(south) => {
-        if !links.iter().any(|linked_cell| *linked_cell == south) {
-          canvas = draw_line(canvas, black, origin, bottom_right);
-        }
-      },
-      None => {
-        canvas = draw_line(canvas, black, origin, bottom_right);
+    let (x, y) = origin;
+    if let Some(up) = grid.up(cell) {
+      if grid.is_linked(&coords, up.coords()) {
+        let tip = (x + cell_size * 7 / 8, y + cell_size * 7 / 8);
+        canvas = draw_line(canvas, wall_color, wall_thickness, (x + cell_size * 5 / 8, y + cell_size * 5 / 8), tip);
+        canvas = draw_line(canvas, wall_color, wall_thickness, (tip.0 - arrow_size, tip.1), tip);
+        canvas = draw_line(canvas, wall_color, wall_thickness, (tip.0, tip.1 - arrow_size), tip);
+      }
+    }
+    if let Some(down) = grid.down(cell) {
+      if grid.is_linked(&coords, down.coords()) {
+        let tip = (x + cell_size / 8, y + cell_size / 8);
+        canvas = draw_line(canvas, wall_color, wall_thickness, (x + cell_size * 3 / 8, y + cell_size * 3 / 8), tip);
+        canvas = draw_line(canvas, wall_color, wall_thickness, (tip.0 + arrow_size, tip.1), tip);
+        canvas = draw_line(canvas, wall_color, wall_thickness, (tip.0, tip.1 + arrow_size), tip);
       }
     }
   }
 
-  let top_y = padding_px + (grid_height_u32 * cell_size);
-  let top_x = padding_px;
-  let top_x2 = padding_px + (grid_width_u32 * cell_size);
-  canvas = draw_line(canvas, black, (top_x, top_y), (top_x2, top_y));
+  canvas = image::imageops::flip_vertical(&canvas);
 
-  let right_y = padding_px;
-  let right_x = padding_px + (grid_width_u32 * cell_size);
-  let right_y2 = padding_px + (grid_height_u32 * cell_size);
-  canvas = draw_line(canvas, black, (right_x, right_y), (right_x, right_y2));
+  Ok(GridImage {
+    canvas,
+    cell_size,
+    padding: padding_px,
+    style: style.clone(),
+  })
+}
+
+// every cell is drawn as a square inset by `inset` of the cell size, with
+// corridors out to the cell edge on open sides. under cells only draw the
+// corridor stubs either side of the cell above them, so crossings show
+pub fn weave_to_img(grid: &WeaveGrid, cell_size: u32, inset: f64) -> GridImage {
+  weave_to_img_with_style(grid, cell_size, inset, &RenderStyle::default())
+}
+
+pub fn weave_to_img_with_style(grid: &WeaveGrid, cell_size: u32, inset: f64, style: &RenderStyle) -> GridImage {
+  match try_weave_to_img_with_style(grid, cell_size, inset, style) {
+    Ok(grid_image) => grid_image,
+    Err(e) => panic!("{}", e)
+  }
+}
+
+pub fn try_weave_to_img(grid: &WeaveGrid, cell_size: u32, inset: f64) -> Result<GridImage, GridError> {
+  try_weave_to_img_with_style(grid, cell_size, inset, &RenderStyle::default())
+}
+
+pub fn try_weave_to_img_with_style(grid: &WeaveGrid, cell_size: u32, inset: f64, style: &RenderStyle) -> Result<GridImage, GridError> {
+  let padding_px = style.padding;
+
+  let (img_width, img_height) = canvas_size(grid.width(), grid.height(), cell_size, padding_px)?;
+  let inset_px = (f64::from(cell_size) * inset.max(0.0).min(0.5)).round() as u32;
+
+  let wall_color = style.wall_color;
+  let wall_thickness = style.wall_thickness;
+
+  let mut canvas: image::RgbImage = image::ImageBuffer::from_pixel(img_width, img_height, style.background);
+  for coords in grid.grid().coords() {
+    let (x, y) = get_origin(padding_px, cell_size, &coords);
+    let (x1, x2) = (x + inset_px, x + cell_size - inset_px);
+    let (y1, y2) = (y + inset_px, y + cell_size - inset_px);
+    let (top, right) = (y + cell_size, x + cell_size);
+
+    let [north, east, south, west] = grid.open_sides(&coords);
+    let mut lines = Vec::new();
+    if north {
+      lines.push(((x1, y2), (x1, top)));
+      lines.push(((x2, y2), (x2, top)));
+    } else {
+      lines.push(((x1, y2), (x2, y2)));
+    }
+    if south {
+      lines.push(((x1, y), (x1, y1)));
+      lines.push(((x2, y), (x2, y1)));
+    } else {
+      lines.push(((x1, y1), (x2, y1)));
+    }
+    if west {
+      lines.push(((x, y1), (x1, y1)));
+      lines.push(((x, y2), (x1, y2)));
+    } else {
+      lines.push(((x1, y1), (x1, y2)));
+    }
+    if east {
+      lines.push(((x2, y1), (right, y1)));
+      lines.push(((x2, y2), (right, y2)));
+    } else {
+      lines.push(((x2, y1), (x2, y2)));
+    }
+
+    match grid.under_at(&coords) {
+      Some(Passage::NorthSouth) => {
+        lines.push(((x1, y), (x1, y1)));
+        lines.push(((x2, y), (x2, y1)));
+        lines.push(((x1, y2), (x1, top)));
+        lines.push(((x2, y2), (x2, top)));
+      }
+      Some(Passage::EastWest) => {
+        lines.push(((x, y1), (x1, y1)));
+        lines.push(((x, y2), (x1, y2)));
+        lines.push(((x2, y1), (right, y1)));
+        lines.push(((x2, y2), (right, y2)));
+      }
+      None => {}
+    }
+
+    for (from, to) in lines {
+      canvas = draw_line(canvas, wall_color, wall_thickness, from, to);
+    }
+  }
 
   canvas = image::imageops::flip_vertical(&canvas);
 
-  GridImage {
+  Ok(GridImage {
     canvas,
     cell_size,
     padding: padding_px,
-  }
+    style: style.clone(),
+  })
 }
 
-pub fn draw_solution(mut grid_image: GridImage, solution: &Vec<GridCoords>) -> GridImage {
+// drawn in the image's solution color and width, with any start and end markers
+pub fn draw_solution(mut grid_image: GridImage, solution: &[GridCoords]) -> GridImage {
   let padding_px = grid_image.padding;
   let cell_size = grid_image.cell_size;
-  let green = image::Rgb { data: [120, 255, 120] };
+  let color = grid_image.style.solution_color;
+  let width = grid_image.style.solution_width;
 
   let mut canvas = image::imageops::flip_vertical(&grid_image.canvas);
 
@@ -159,12 +771,24 @@ pub fn draw_solution(mut grid_image: GridImage, solution: &Vec<GridCoords>) -> G
   for coords in solution {
     let origin = get_origin(padding_px, cell_size, coords);
     let center_point = get_point(origin, cell_size, CellPoint::Center);
-    canvas.put_pixel(center_point.0, center_point.1, green);
+    draw_point(&mut canvas, color, width, center_point);
     if let Some(trailing_center_point) = trailing_point {
-      canvas = draw_line(canvas, green, trailing_center_point, center_point);
+      canvas = draw_line(canvas, color, width, trailing_center_point, center_point);
     }
     trailing_point = Some(center_point);
   }
+
+  // a square half the size of the cell in the middle of it
+  let markers = vec![
+    (solution.first(), grid_image.style.start_marker),
+    (solution.last(), grid_image.style.end_marker),
+  ];
+  for (coords, marker) in markers {
+    if let (Some(coords), Some(marker)) = (coords, marker) {
+      let origin = get_origin(padding_px, cell_size, coords);
+      canvas = fill_square(canvas, marker, (origin.0 + cell_size / 4, origin.1 + cell_size / 4), cell_size / 2);
+    }
+  }
   grid_image.canvas = image::imageops::flip_vertical(&canvas);
   grid_image
 }
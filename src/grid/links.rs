@@ -0,0 +1,85 @@
+// implements `links`, `is_linked`, `link`, `link_bidi`, `unlink`,
+// `unlink_bidi`, `unlink_bidi_all` and `clear_links` for a grid of `$cell`s
+// keyed by `$coords`, going through the grid's `graph`. with `walls` it
+// also implements `has_wall` for the grids whose renderers walk cells
+macro_rules! impl_links {
+  ($grid:ident, $cell:ty, $coords:ty, walls) => {
+    impl_links!(@impl $grid, $cell, $coords, {
+      // whether `cell` has a wall on the side facing `neighbor`
+      pub(crate) fn has_wall(&self, cell: &$cell, neighbor: Option<&$cell>) -> bool {
+        match neighbor {
+          Some(neighbor) => !self.is_linked(cell.coords(), neighbor.coords()),
+          None => true
+        }
+      }
+    });
+  };
+  ($grid:ident, $cell:ty, $coords:ty) => {
+    impl_links!(@impl $grid, $cell, $coords, {});
+  };
+  (@impl $grid:ident, $cell:ty, $coords:ty, { $($extra:tt)* }) => {
+    impl $grid {
+      pub fn links(&self, cell: &$cell) -> Vec<&$cell> {
+        self.graph.get_adjacent(cell.coords()).into_iter().map(|coords| {
+          self.cell_at(coords).unwrap()
+        }).collect()
+      }
+
+      pub fn is_linked(&self, source: &$coords, destination: &$coords) -> bool {
+        self.graph.get_adjacent(source).into_iter().any(|coords| coords == destination)
+      }
+
+      pub fn link(&mut self, source: &$coords, destination: &$coords) {
+        if !self.is_linked(source, destination) {
+          self.graph.add_edge(source.clone(), destination.clone());
+        }
+      }
+
+      pub fn link_bidi(&mut self, source: &$coords, destination: &$coords) {
+        self.link(source, destination);
+        self.link(destination, source);
+      }
+
+      pub fn unlink(&mut self, source: &$coords, destination: &$coords) {
+        self.remove_edges(vec![(source.clone(), destination.clone())].into_iter().collect());
+      }
+
+      pub fn unlink_bidi(&mut self, source: &$coords, destination: &$coords) {
+        self.unlink_bidi_all(&[(source.clone(), destination.clone())]);
+      }
+
+      // unlinks every pair both ways. each unlink rebuilds the graph, so this
+      // is much quicker than unlinking the pairs one at a time
+      pub fn unlink_bidi_all(&mut self, pairs: &[($coords, $coords)]) {
+        self.remove_edges(pairs.iter().flat_map(|(source, destination)| {
+          vec![(source.clone(), destination.clone()), (destination.clone(), source.clone())]
+        }).collect());
+      }
+
+      pub fn clear_links(&mut self) {
+        self.graph = gust::Graph::new();
+      }
+
+      // a gust `Graph` can't drop a single edge, so the graph is rebuilt
+      // from every edge but these
+      fn remove_edges(&mut self, edges: std::collections::HashSet<($coords, $coords)>) {
+        if !edges.iter().any(|(source, destination)| self.is_linked(source, destination)) {
+          return;
+        }
+        let mut graph = gust::Graph::new();
+        for source in self.coords() {
+          for destination in self.graph.get_adjacent(&source) {
+            if !edges.contains(&(source.clone(), destination.clone())) {
+              graph.add_edge(source.clone(), destination.clone());
+            }
+          }
+        }
+        self.graph = graph;
+      }
+
+      $($extra)*
+    }
+  };
+}
+
+pub(crate) use impl_links;
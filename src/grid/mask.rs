@@ -0,0 +1,100 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use super::cell::GridCoords;
+
+// which cells of a rectangle exist. the constructors all take their rows top
+// first, the way they're written in a text file or stored in an image, while
+// `GridCoords` count rows from the bottom
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mask {
+  height: usize,
+  width: usize,
+  bits: Vec<bool>,
+}
+
+impl Mask {
+  pub fn new(height: usize, width: usize) -> Mask {
+    Mask {
+      height,
+      width,
+      bits: vec![true; height * width],
+    }
+  }
+
+  // rows may be ragged, missing cells at the end of a short row are on
+  pub fn from_bools(rows: &[Vec<bool>]) -> Mask {
+    let height = rows.len();
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut mask = Mask::new(height, width);
+    for (line_index, row) in rows.iter().enumerate() {
+      let row_index = height - 1 - line_index;
+      for (col_index, on) in row.iter().enumerate() {
+        mask.set(&GridCoords {
+          col_index,
+          row_index,
+        }, *on);
+      }
+    }
+    mask
+  }
+
+  // `X` (either case) marks a cell as off, anything else is on
+  pub fn from_text(text: &str) -> Mask {
+    let rows: Vec<Vec<bool>> = text.lines().map(|line| {
+      line.trim_end_matches('\r').chars().map(|c| c != 'X' && c != 'x').collect()
+    }).collect();
+    Mask::from_bools(&rows)
+  }
+
+  pub fn from_text_file<P: AsRef<Path>>(path: P) -> io::Result<Mask> {
+    let text = fs::read_to_string(path)?;
+    Ok(Mask::from_text(&text))
+  }
+
+  // one cell per pixel, dark pixels are off
+  pub fn from_png<P: AsRef<Path>>(path: P) -> image::ImageResult<Mask> {
+    let img = image::open(path)?.to_rgb();
+    let (width, height) = img.dimensions();
+    let rows: Vec<Vec<bool>> = (0..height).map(|y| {
+      (0..width).map(|x| {
+        let [r, g, b] = img.get_pixel(x, y).data;
+        (u32::from(r) + u32::from(g) + u32::from(b)) / 3 >= 128
+      }).collect()
+    }).collect();
+    Ok(Mask::from_bools(&rows))
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  fn index(&self, coords: &GridCoords) -> Option<usize> {
+    if coords.row_index >= self.height || coords.col_index >= self.width {
+      return None;
+    }
+    Some(coords.row_index * self.width + coords.col_index)
+  }
+
+  // anything outside the mask is off
+  pub fn is_on(&self, coords: &GridCoords) -> bool {
+    match self.index(coords) {
+      Some(idx) => self.bits[idx],
+      None => false
+    }
+  }
+
+  pub fn set(&mut self, coords: &GridCoords, on: bool) {
+    if let Some(idx) = self.index(coords) {
+      self.bits[idx] = on;
+    }
+  }
+
+  pub fn count(&self) -> usize {
+    self.bits.iter().filter(|on| **on).count()
+  }
+}
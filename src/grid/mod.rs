@@ -1,12 +1,30 @@
 pub mod cell;
+pub mod error;
+pub mod grid3d;
+pub mod hex;
 pub mod img;
+mod links;
+pub mod mask;
+pub mod polar;
+pub mod svg;
+pub mod text;
+pub mod topology;
+pub mod triangle;
+pub mod weave;
 
 use std::collections::HashMap;
 use std::fmt;
+use std::io;
 use gust::Graph;
 use rand::Rng;
+use rand::seq::SliceRandom;
 use cell::*;
 use img::*;
+use error::GridError;
+use svg::*;
+use mask::Mask;
+use links::impl_links;
+use topology::impl_topology;
 
 #[derive(Debug)]
 pub struct Neighbors<'a> {
@@ -16,21 +34,47 @@ pub struct Neighbors<'a> {
   pub west: Option<&'a Cell>
 }
 
+impl<'a> Neighbors<'a> {
+  pub fn to_vec(&self) -> Vec<&'a Cell> {
+    vec![self.north, self.east, self.south, self.west].into_iter().flatten().collect()
+  }
+}
+
+// how the edges of a `Grid` join up. a cylinder wraps the east edge round
+// to the west edge, a torus also wraps the north edge round to the south
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+  Flat,
+  Cylinder,
+  Torus,
+}
+
 #[derive(Debug)]
 pub struct Grid {
   height: usize,
   width: usize,
+  wrap: Wrap,
   cells: HashMap<GridCoords, Cell>,
   graph: Graph<Cell>,
+  weights: HashMap<GridCoords, u32>,
+  // keyed by the lower coords first, a link costs the same both ways
+  link_weights: HashMap<(GridCoords, GridCoords), u32>,
 }
 
 impl Grid {
   pub fn new(height: usize, width: usize) -> Grid {
+    Grid::with_wrap(height, width, Wrap::Flat)
+  }
+
+  pub fn with_wrap(height: usize, width: usize, wrap: Wrap) -> Grid {
     let mut grid = Grid {
       height,
       width,
+      wrap,
       cells: HashMap::new(),
       graph: Graph::new(),
+      weights: HashMap::new(),
+      link_weights: HashMap::new(),
     };
     for col_index in 0..width {
       for row_index in 0..height {
@@ -45,6 +89,34 @@ impl Grid {
     grid
   }
 
+  // only the cells switched on in the mask exist. binary tree, sidewinder,
+  // Eller's and recursive division panic on a masked grid, Aldous-Broder and
+  // Wilson's panic unless every cell is reachable from every other
+  pub fn from_mask(mask: &Mask) -> Grid {
+    let mut grid = Grid {
+      height: mask.height(),
+      width: mask.width(),
+      wrap: Wrap::Flat,
+      cells: HashMap::new(),
+      graph: Graph::new(),
+      weights: HashMap::new(),
+      link_weights: HashMap::new(),
+    };
+    for col_index in 0..grid.width {
+      for row_index in 0..grid.height {
+        let coords = GridCoords {
+          col_index,
+          row_index
+        };
+        if mask.is_on(&coords) {
+          let cell = Cell::new(&coords);
+          grid.cells.insert(coords, cell);
+        }
+      }
+    }
+    grid
+  }
+
   pub fn cells(&self) -> &HashMap<GridCoords, Cell> {
     &self.cells
   }
@@ -68,10 +140,14 @@ impl Grid {
       }
       let mut coords = Vec::new();
       for col_index in 0..self.width {
-        coords.push(GridCoords {
+        let cell_coords = GridCoords {
           col_index,
           row_index
-        });
+        };
+        // skip over cells masked out of the grid
+        if self.cells.contains_key(&cell_coords) {
+          coords.push(cell_coords);
+        }
       }
       rows.push(coords);
     }
@@ -82,10 +158,13 @@ impl Grid {
     let mut coords = Vec::new();
     for col_index in 0..self.width {
       for row_index in 0..self.height {
-        coords.push(GridCoords {
+        let cell_coords = GridCoords {
           col_index,
           row_index
-        });
+        };
+        if self.cells.contains_key(&cell_coords) {
+          coords.push(cell_coords);
+        }
       }
     }
     coords
@@ -100,8 +179,18 @@ impl Grid {
   }
 
   pub fn rand_cell(&self) -> &Cell {
-     let row_index = rand::thread_rng().gen_range(0, self.width);
-     let col_index = rand::thread_rng().gen_range(0, self.height);
+    self.rand_cell_with_rng(&mut rand::thread_rng())
+  }
+
+  pub fn rand_cell_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> &Cell {
+     // a mask may have removed some cells, pick from the ones that exist
+     if self.is_masked() {
+       let coords = self.coords();
+       let idx = rng.gen_range(0, coords.len());
+       return self.cell_at(&coords[idx]).unwrap();
+     }
+     let row_index = rng.gen_range(0, self.height);
+     let col_index = rng.gen_range(0, self.width);
      // we can unwrap here since the calculation is
      // bound to the size of the grid
      self.cell_at(&GridCoords {
@@ -119,72 +208,213 @@ impl Grid {
     }
   }
 
-  pub fn links(&self, cell: &Cell) -> Vec<&Cell> {
-    self.graph.get_adjacent(cell.coords()).into_iter().map(|coords| {
-      self.cell_at(coords).unwrap()
+  // links every cell to all of its neighbors, leaving no inner walls
+  pub fn link_all(&mut self) {
+    for coords in self.coords() {
+      let cell = self.cell_at(&coords).unwrap();
+      let north = self.north(cell).map(|north| *north.coords());
+      let east = self.east(cell).map(|east| *east.coords());
+      if let Some(north) = north {
+        self.link_bidi(&coords, &north);
+      }
+      if let Some(east) = east {
+        self.link_bidi(&coords, &east);
+      }
+    }
+  }
+
+  // what it costs to step into the cell, 1 unless it's been set
+  pub fn weight(&self, coords: &GridCoords) -> u32 {
+    self.weights.get(coords).cloned().unwrap_or(1)
+  }
+
+  pub fn set_weight(&mut self, coords: &GridCoords, weight: u32) {
+    self.weights.insert(*coords, weight);
+  }
+
+  fn link_key(source: &GridCoords, destination: &GridCoords) -> (GridCoords, GridCoords) {
+    if source <= destination {
+      (*source, *destination)
+    } else {
+      (*destination, *source)
+    }
+  }
+
+  // what it costs to go through the link between two cells either way, if
+  // it's been set
+  pub fn link_weight(&self, source: &GridCoords, destination: &GridCoords) -> Option<u32> {
+    self.link_weights.get(&Grid::link_key(source, destination)).cloned()
+  }
+
+  pub fn set_link_weight(&mut self, source: &GridCoords, destination: &GridCoords, weight: u32) {
+    self.link_weights.insert(Grid::link_key(source, destination), weight);
+  }
+
+  // the cost of stepping from `source` into `destination`, the link's
+  // weight where there is one, otherwise the weight of `destination`
+  pub fn cost(&self, source: &GridCoords, destination: &GridCoords) -> u32 {
+    match self.link_weight(source, destination) {
+      Some(weight) => weight,
+      None => self.weight(destination)
+    }
+  }
+
+  // the least any step can cost, 1 unless weights have made some cheaper
+  // or every cell has been given a dearer weight
+  pub fn min_cost(&self) -> u32 {
+    let unweighted = self.coords().iter().any(|coords| !self.weights.contains_key(coords));
+    let default = if unweighted { Some(1) } else { None };
+    self.weights.values().chain(self.link_weights.values()).cloned().chain(default).min().unwrap_or(1)
+  }
+
+  // cells with only one way in or out
+  pub fn dead_ends(&self) -> Vec<GridCoords> {
+    self.coords().into_iter().filter(|coords| {
+      self.links(self.cell_at(coords).unwrap()).len() == 1
     }).collect()
   }
 
-  pub fn link(&mut self, source: &GridCoords, destination: &GridCoords) {
-    self.graph.add_edge(source.clone(), destination.clone());
+  // links each dead end to one of its neighbors with probability `p`,
+  // preferring a neighbor that's a dead end too so both go at once
+  pub fn braid(&mut self, p: f64) {
+    self.braid_with_rng(p, &mut rand::thread_rng())
   }
 
-  pub fn link_bidi(&mut self, source: &GridCoords, destination: &GridCoords) {
-    self.graph.add_edge_bidi(source.clone(), destination.clone());
+  pub fn braid_with_rng<R: Rng + ?Sized>(&mut self, p: f64, rng: &mut R) {
+    let mut dead_ends = self.dead_ends();
+    dead_ends.shuffle(rng);
+    for coords in dead_ends {
+      let cell = self.cell_at(&coords).unwrap();
+      // an earlier dead end may have been linked to this one already
+      if self.links(cell).len() != 1 || rng.gen::<f64>() >= p {
+        continue;
+      }
+      let unlinked: Vec<GridCoords> = self.neighbors(cell).to_vec().into_iter()
+        .filter(|neighbor| !self.is_linked(&coords, neighbor.coords()))
+        .map(|neighbor| *neighbor.coords())
+        .collect();
+      let mut best: Vec<GridCoords> = unlinked.iter()
+        .filter(|neighbor| self.links(self.cell_at(neighbor).unwrap()).len() == 1)
+        .cloned()
+        .collect();
+      if best.is_empty() {
+        best = unlinked;
+      }
+      if let Some(neighbor) = best.choose(rng) {
+        let neighbor = *neighbor;
+        self.link_bidi(&coords, &neighbor);
+      }
+    }
+  }
+
+  // whether a wall separates the cell position `coords` from `other`, where
+  // `other` is None beyond the edge of the grid. either position may be a
+  // masked out cell, there's only a wall where a cell borders something else.
+  // the renderers walk every position rather than every cell so the walls
+  // around masked out cells still get drawn
+  pub(crate) fn has_wall(&self, coords: &GridCoords, other: Option<&GridCoords>) -> bool {
+    let here = self.cell_at(coords).is_some();
+    match other.filter(|other| self.cell_at(other).is_some()) {
+      Some(other) => !here || !self.is_linked(coords, other),
+      None => here
+    }
   }
 
   pub fn height(&self) -> usize {
     self.height
   }
 
+  pub fn wrap(&self) -> Wrap {
+    self.wrap
+  }
+
+  // whether a mask removed any of the cells
+  pub fn is_masked(&self) -> bool {
+    self.cells.len() < self.height * self.width
+  }
+
+  // a grid two cells or less across already has every neighbor it could
+  // wrap round to, so it only wraps when it's wider (or taller) than that
+  pub(crate) fn wraps_east(&self) -> bool {
+    self.wrap != Wrap::Flat && self.width > 2
+  }
+
+  pub(crate) fn wraps_north(&self) -> bool {
+    self.wrap == Wrap::Torus && self.height > 2
+  }
+
+  // the positions next to `coords`, wrapping round the edges of the grid.
+  // the position may be masked out
+  pub(crate) fn north_of(&self, coords: &GridCoords) -> Option<GridCoords> {
+    let row_index = if coords.row_index + 1 < self.height {
+      coords.row_index + 1
+    } else if self.wraps_north() {
+      0
+    } else {
+      return None
+    };
+    Some(GridCoords {
+      row_index,
+      col_index: coords.col_index
+    })
+  }
+
+  pub(crate) fn east_of(&self, coords: &GridCoords) -> Option<GridCoords> {
+    let col_index = if coords.col_index + 1 < self.width {
+      coords.col_index + 1
+    } else if self.wraps_east() {
+      0
+    } else {
+      return None
+    };
+    Some(GridCoords {
+      row_index: coords.row_index,
+      col_index
+    })
+  }
+
+  pub(crate) fn south_of(&self, coords: &GridCoords) -> Option<GridCoords> {
+    let row_index = match coords.row_index.checked_sub(1) {
+      Some(row_index) => row_index,
+      None if self.wraps_north() => self.height - 1,
+      None => return None
+    };
+    Some(GridCoords {
+      row_index,
+      col_index: coords.col_index
+    })
+  }
+
+  pub(crate) fn west_of(&self, coords: &GridCoords) -> Option<GridCoords> {
+    let col_index = match coords.col_index.checked_sub(1) {
+      Some(col_index) => col_index,
+      None if self.wraps_east() => self.width - 1,
+      None => return None
+    };
+    Some(GridCoords {
+      row_index: coords.row_index,
+      col_index
+    })
+  }
+
   pub fn width(&self) -> usize {
     self.width
   }
 
   pub fn north(&self, cell: &Cell) -> Option<&Cell> {
-    let north_coords = cell.north_coords();
-    match north_coords {
-      Some(coords) => {
-        if coords.row_index >= self.height {
-          return None
-        }
-        self.cell_at(&coords)
-      }
-      None => None
-    }
+    self.north_of(cell.coords()).and_then(|coords| self.cell_at(&coords))
   }
 
   pub fn east(&self, cell: &Cell) -> Option<&Cell> {
-    let east_coords = cell.east_coords();
-    match east_coords {
-      Some(coords) => {
-        if coords.col_index >= self.width {
-          return None
-        }
-        self.cell_at(&coords)
-      }
-      None => None
-    }
+    self.east_of(cell.coords()).and_then(|coords| self.cell_at(&coords))
   }
 
   pub fn south(&self, cell: &Cell) -> Option<&Cell> {
-    let south_coords = cell.south_coords();
-    match south_coords {
-      Some(coords) => {
-        self.cell_at(&coords)
-      }
-      None => None
-    }
+    self.south_of(cell.coords()).and_then(|coords| self.cell_at(&coords))
   }
 
   pub fn west(&self, cell: &Cell) -> Option<&Cell> {
-    let west_coords = cell.west_coords();
-    match west_coords {
-      Some(coords) => {
-        self.cell_at(&coords)
-      }
-      None => None
-    }
+    self.west_of(cell.coords()).and_then(|coords| self.cell_at(&coords))
   }
 
   pub fn to_img(&self, path: &str, cell_size: u32) -> GridImage {
@@ -195,58 +425,142 @@ impl Grid {
 
   pub fn to_img_with_solution(&self, path: &str, cell_size: u32, solution: &Vec<GridCoords>) -> GridImage {
     let mut grid_image = to_img(self, cell_size);
-    grid_image = draw_solution(grid_image, solution);
+    grid_image = img::draw_solution(grid_image, solution);
     grid_image.canvas.save(path).unwrap();
     grid_image
   }
+
+  // like `to_img` but returns an error rather than panicking, nothing is
+  // drawn if the path's extension can't be written
+  pub fn try_to_img(&self, path: &str, cell_size: u32) -> Result<GridImage, GridError> {
+    check_extension(path.as_ref())?;
+    let grid_image = try_to_img(self, cell_size)?;
+    grid_image.save(path)?;
+    Ok(grid_image)
+  }
+
+  pub fn try_to_img_with_solution(&self, path: &str, cell_size: u32, solution: &[GridCoords]) -> Result<GridImage, GridError> {
+    check_extension(path.as_ref())?;
+    let grid_image = img::draw_solution(try_to_img(self, cell_size)?, solution);
+    grid_image.save(path)?;
+    Ok(grid_image)
+  }
+
+  pub fn write_png<W: io::Write>(&self, writer: W, cell_size: u32) -> Result<(), GridError> {
+    try_to_img(self, cell_size)?.write_png(writer)
+  }
+
+  pub fn to_svg(&self, cell_size: u32) -> String {
+    to_svg(self, cell_size).to_string()
+  }
+
+  pub fn to_svg_with_solution(&self, cell_size: u32, solution: &[GridCoords]) -> String {
+    svg::draw_solution(to_svg(self, cell_size), solution).to_string()
+  }
+
+  pub fn to_svg_with_style(&self, cell_size: u32, style: &RenderStyle) -> String {
+    to_svg_with_style(self, cell_size, style).to_string()
+  }
+
+  // `Display` with box drawing characters
+  pub fn to_box_drawing(&self) -> String {
+    text::to_box_drawing(self, None)
+  }
+
+  pub fn to_box_drawing_with_distances(&self, distances: &HashMap<GridCoords, u32>) -> String {
+    text::to_box_drawing(self, Some(text::Overlay::Distances(distances)))
+  }
+
+  pub fn to_box_drawing_with_path(&self, path: &[GridCoords]) -> String {
+    text::to_box_drawing(self, Some(text::Overlay::Path(path)))
+  }
+
+  pub fn to_half_blocks(&self) -> String {
+    text::to_half_blocks(self)
+  }
+
+  pub fn write_svg<W: io::Write>(&self, writer: &mut W, cell_size: u32) -> io::Result<()> {
+    to_svg(self, cell_size).write_to(writer)
+  }
+
+  pub fn write_svg_with_solution<W: io::Write>(&self, writer: &mut W, cell_size: u32, solution: &[GridCoords]) -> io::Result<()> {
+    svg::draw_solution(to_svg(self, cell_size), solution).write_to(writer)
+  }
+
+  pub fn write_svg_with_style<W: io::Write>(&self, writer: &mut W, cell_size: u32, style: &RenderStyle) -> io::Result<()> {
+    to_svg_with_style(self, cell_size, style).write_to(writer)
+  }
 }
 
+impl_links!(Grid, Cell, GridCoords);
+
+impl_topology!(Grid, weighted);
+
 impl fmt::Display for Grid {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    let top_corner = String::from("+");
-    let top_border = String::from("---+").repeat(self.width);
-    let mut lines = vec![format!("{}{}", top_corner, top_border)];
+    let mut top_border = String::from("+");
+    for col_index in 0..self.width {
+      let top_wall = match self.height.checked_sub(1) {
+        Some(row_index) => {
+          let coords = GridCoords {
+            col_index,
+            row_index
+          };
+          self.has_wall(&coords, self.north_of(&coords).as_ref())
+        }
+        None => true
+      };
+      if top_wall {
+        top_border.push_str("---+");
+      } else {
+        top_border.push_str("   +");
+      }
+    }
+    let mut lines = vec![top_border];
 
-    let rows = self.rows_reverse();
-    for row in rows {
+    for row_index in (0..self.height).rev() {
       let mut top = String::new();
       let mut bottom = String::new();
 
-      for cell_id in row {
-        let neighbors = self.neighbors(self.cell_at(&cell_id).unwrap());
-        let links = self.links(self.cell_at(&cell_id).unwrap());
-
-        match neighbors.west {
-          None => {
-            top.push_str("|");
-          },
-          Some(cell) => {
-            if links.iter().any(|linked_cell| *linked_cell == cell) {
-              top.push_str(" ");
-            } else {
-              top.push_str("|");
-            }
-          }
+      for col_index in 0..self.width {
+        let cell_id = GridCoords {
+          col_index,
+          row_index
+        };
+        let west = self.west_of(&cell_id);
+        let south = self.south_of(&cell_id);
+
+        if self.has_wall(&cell_id, west.as_ref()) {
+          top.push('|');
+        } else {
+          top.push(' ');
         }
-        bottom.push_str("+");
+        bottom.push('+');
 
         top.push_str("   ");
-        match neighbors.south {
-          None => {
-            bottom.push_str("---");
-          },
-          Some(cell) => {
-            if links.iter().any(|linked_cell| *linked_cell == cell) {
-              bottom.push_str("   ");
-            } else {
-              bottom.push_str("---");
-            }
-          }
+        if self.has_wall(&cell_id, south.as_ref()) {
+          bottom.push_str("---");
+        } else {
+          bottom.push_str("   ");
         }
       }
 
-      top.push_str("|");
-      bottom.push_str("+");
+      let east_wall = match self.width.checked_sub(1) {
+        Some(col_index) => {
+          let coords = GridCoords {
+            col_index,
+            row_index
+          };
+          self.has_wall(&coords, self.east_of(&coords).as_ref())
+        }
+        None => true
+      };
+      if east_wall {
+        top.push('|');
+      } else {
+        top.push(' ');
+      }
+      bottom.push('+');
       lines.push(top);
       lines.push(bottom);
     }
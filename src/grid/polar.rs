@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use gust::Graph;
+use rand::Rng;
+use super::cell::*;
+use super::error::GridError;
+use super::img::*;
+use super::links::impl_links;
+use super::topology::impl_topology;
+
+#[derive(Debug)]
+pub struct PolarNeighbors<'a> {
+  pub cw: Option<&'a Cell>,
+  pub ccw: Option<&'a Cell>,
+  pub inward: Option<&'a Cell>,
+  pub outward: Vec<&'a Cell>,
+}
+
+impl<'a> PolarNeighbors<'a> {
+  pub fn to_vec(&self) -> Vec<&'a Cell> {
+    let mut cells: Vec<&'a Cell> = vec![self.cw, self.ccw, self.inward].into_iter().flatten().collect();
+    cells.extend(self.outward.iter());
+    cells
+  }
+}
+
+// concentric rings of cells around a single center cell. `row_index` is the
+// ring, counting out from the center, and `col_index` runs clockwise around
+// the ring. rings are split into more cells as they get further out so the
+// cells stay roughly square
+#[derive(Debug)]
+pub struct PolarGrid {
+  row_lengths: Vec<usize>,
+  cells: HashMap<GridCoords, Cell>,
+  graph: Graph<Cell>,
+}
+
+impl PolarGrid {
+  pub fn new(height: usize) -> PolarGrid {
+    let mut row_lengths = Vec::with_capacity(height);
+    if height > 0 {
+      row_lengths.push(1);
+    }
+    let row_height = 1.0 / height as f64;
+    for row_index in 1..height {
+      let radius = row_index as f64 / height as f64;
+      let circumference = 2.0 * PI * radius;
+      let previous_length = row_lengths[row_index - 1];
+      let estimated_cell_width = circumference / previous_length as f64;
+      let ratio = (estimated_cell_width / row_height).round().max(1.0) as usize;
+      row_lengths.push(previous_length * ratio);
+    }
+
+    let mut grid = PolarGrid {
+      row_lengths,
+      cells: HashMap::new(),
+      graph: Graph::new(),
+    };
+    for coords in grid.coords() {
+      let cell = Cell::new(&coords);
+      grid.cells.insert(coords, cell);
+    }
+    grid
+  }
+
+  // number of rings, including the center
+  pub fn height(&self) -> usize {
+    self.row_lengths.len()
+  }
+
+  pub fn row_length(&self, row_index: usize) -> usize {
+    self.row_lengths.get(row_index).cloned().unwrap_or(0)
+  }
+
+  pub fn cells(&self) -> &HashMap<GridCoords, Cell> {
+    &self.cells
+  }
+
+  pub fn cell_at(&self, coords: &GridCoords) -> Option<&Cell> {
+    self.cells.get(coords)
+  }
+
+  pub fn coords(&self) -> Vec<GridCoords> {
+    self.rows().into_iter().flatten().collect()
+  }
+
+  // rings from the center outward
+  pub fn rows(&self) -> Vec<Vec<GridCoords>> {
+    self.row_lengths.iter().enumerate().map(|(row_index, length)| {
+      (0..*length).map(|col_index| GridCoords {
+        col_index,
+        row_index
+      }).collect()
+    }).collect()
+  }
+
+  pub fn rand_cell(&self) -> &Cell {
+    self.rand_cell_with_rng(&mut rand::thread_rng())
+  }
+
+  pub fn rand_cell_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> &Cell {
+    let row_index = rng.gen_range(0, self.height());
+    let col_index = rng.gen_range(0, self.row_length(row_index));
+    self.cell_at(&GridCoords {
+      col_index,
+      row_index
+    }).unwrap()
+  }
+
+  pub fn cw(&self, cell: &Cell) -> Option<&Cell> {
+    let coords = cell.coords();
+    let length = self.row_length(coords.row_index);
+    if length < 2 {
+      return None;
+    }
+    self.cell_at(&GridCoords {
+      col_index: (coords.col_index + 1) % length,
+      row_index: coords.row_index
+    })
+  }
+
+  pub fn ccw(&self, cell: &Cell) -> Option<&Cell> {
+    let coords = cell.coords();
+    let length = self.row_length(coords.row_index);
+    if length < 2 {
+      return None;
+    }
+    self.cell_at(&GridCoords {
+      col_index: (coords.col_index + length - 1) % length,
+      row_index: coords.row_index
+    })
+  }
+
+  pub fn inward(&self, cell: &Cell) -> Option<&Cell> {
+    let coords = cell.coords();
+    if coords.row_index == 0 {
+      return None;
+    }
+    let ratio = self.row_length(coords.row_index) / self.row_length(coords.row_index - 1);
+    self.cell_at(&GridCoords {
+      col_index: coords.col_index / ratio,
+      row_index: coords.row_index - 1
+    })
+  }
+
+  pub fn outward(&self, cell: &Cell) -> Vec<&Cell> {
+    let coords = cell.coords();
+    let outer_length = self.row_length(coords.row_index + 1);
+    if outer_length == 0 {
+      return Vec::new();
+    }
+    let ratio = outer_length / self.row_length(coords.row_index);
+    (coords.col_index * ratio..(coords.col_index + 1) * ratio).filter_map(|col_index| {
+      self.cell_at(&GridCoords {
+        col_index,
+        row_index: coords.row_index + 1
+      })
+    }).collect()
+  }
+
+  pub fn neighbors(&self, cell: &Cell) -> PolarNeighbors<'_> {
+    PolarNeighbors {
+      cw: self.cw(cell),
+      ccw: self.ccw(cell),
+      inward: self.inward(cell),
+      outward: self.outward(cell),
+    }
+  }
+
+  pub fn to_img(&self, path: &str, cell_size: u32) -> GridImage {
+    match self.try_to_img(path, cell_size) {
+      Ok(grid_image) => grid_image,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  // like `to_img` but returns an error rather than panicking, nothing is
+  // drawn if the path's extension can't be written
+  pub fn try_to_img(&self, path: &str, cell_size: u32) -> Result<GridImage, GridError> {
+    check_extension(path.as_ref())?;
+    let grid_image = try_polar_to_img(self, cell_size)?;
+    grid_image.save(path)?;
+    Ok(grid_image)
+  }
+}
+
+impl_links!(PolarGrid, Cell, GridCoords);
+
+impl_topology!(PolarGrid);
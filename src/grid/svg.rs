@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::convert::TryFrom;
+use super::{Grid, GridCoords};
+use super::error::GridError;
+use super::img::{modify_color_by_distance, RenderStyle};
+
+// an svg of a grid built up in layers, distance colors at the bottom, then
+// the walls, then any solution on top
+pub struct GridSvg {
+  pub width: u32,
+  pub height: u32,
+  pub cell_size: u32,
+  pub padding: u32,
+  // rows in the grid, needed to turn them upside down
+  pub rows: u32,
+  pub fills: Vec<String>,
+  pub walls: Vec<String>,
+  pub overlays: Vec<String>,
+  // what it was drawn with, anything drawn on top follows it too
+  pub style: RenderStyle,
+}
+
+impl GridSvg {
+  pub fn write_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    write!(writer, "{}", self)
+  }
+}
+
+impl fmt::Display for GridSvg {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#, self.width, self.height)?;
+    writeln!(f, r#"<rect width="100%" height="100%" fill="{}"/>"#, rgb(self.style.background()))?;
+    for fill in &self.fills {
+      writeln!(f, "{}", fill)?;
+    }
+    writeln!(f, r#"<g stroke="{}" stroke-width="{}" stroke-linecap="square">"#, rgb(self.style.wall_color()), self.style.wall_thickness())?;
+    for wall in &self.walls {
+      writeln!(f, "{}", wall)?;
+    }
+    writeln!(f, "</g>")?;
+    for overlay in &self.overlays {
+      writeln!(f, "{}", overlay)?;
+    }
+    writeln!(f, "</svg>")
+  }
+}
+
+fn rgb(color: image::Rgb<u8>) -> String {
+  let [r, g, b] = color.data;
+  format!("rgb({},{},{})", r, g, b)
+}
+
+fn line((x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> String {
+  format!(r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#, x1, y1, x2, y2)
+}
+
+// the top left corner of a cell, svg runs top down so row 0 is at the bottom
+fn get_origin(padding: u32, cell_size: u32, height: u32, cell: &GridCoords) -> (u32, u32) {
+  let row_from_top = height - 1 - u32::try_from(cell.row_index).unwrap();
+  let origin_x = padding + (u32::try_from(cell.col_index).unwrap() * cell_size);
+  let origin_y = padding + (row_from_top * cell_size);
+  (origin_x, origin_y)
+}
+
+pub fn to_svg(grid: &Grid, cell_size: u32) -> GridSvg {
+  to_svg_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+pub fn to_svg_with_style(grid: &Grid, cell_size: u32, style: &RenderStyle) -> GridSvg {
+  match try_to_svg_with_style(grid, cell_size, style) {
+    Ok(grid_svg) => grid_svg,
+    Err(e) => panic!("{}", e)
+  }
+}
+
+pub fn try_to_svg(grid: &Grid, cell_size: u32) -> Result<GridSvg, GridError> {
+  try_to_svg_with_style(grid, cell_size, &RenderStyle::default())
+}
+
+// the svg's width and height in pixels, or an error if either doesn't fit in a `u32`
+fn svg_size(grid: &Grid, cell_size: u32, padding: u32) -> Result<(u32, u32), GridError> {
+  let too_large = || GridError::CanvasTooLarge {
+    width: grid.width,
+    height: grid.height,
+    cell_size,
+  };
+  let padding_total = padding.checked_mul(2).ok_or_else(too_large)?;
+  let side = |cells: usize| u32::try_from(cells).ok()
+    .and_then(|cells| cells.checked_mul(cell_size))
+    .and_then(|pixels| pixels.checked_add(padding_total));
+  Ok((side(grid.width).ok_or_else(too_large)?, side(grid.height).ok_or_else(too_large)?))
+}
+
+pub fn try_to_svg_with_style(grid: &Grid, cell_size: u32, style: &RenderStyle) -> Result<GridSvg, GridError> {
+  let padding_px = style.padding();
+  let (width, height) = svg_size(grid, cell_size, padding_px)?;
+  let grid_height_u32 = u32::try_from(grid.height).unwrap();
+
+  let mut walls = Vec::new();
+  for row_index in 0..grid.height {
+    for col_index in 0..grid.width {
+      let coords = GridCoords {
+        col_index,
+        row_index
+      };
+      let (x1, y1) = get_origin(padding_px, cell_size, grid_height_u32, &coords);
+      let (x2, y2) = (x1 + cell_size, y1 + cell_size);
+
+      if grid.has_wall(&coords, grid.west_of(&coords).as_ref()) {
+        walls.push(line((x1, y1), (x1, y2)));
+      }
+      if grid.has_wall(&coords, grid.south_of(&coords).as_ref()) {
+        walls.push(line((x1, y2), (x2, y2)));
+      }
+      if row_index + 1 == grid.height && grid.has_wall(&coords, grid.north_of(&coords).as_ref()) {
+        walls.push(line((x1, y1), (x2, y1)));
+      }
+      if col_index + 1 == grid.width && grid.has_wall(&coords, grid.east_of(&coords).as_ref()) {
+        walls.push(line((x2, y1), (x2, y2)));
+      }
+    }
+  }
+
+  Ok(GridSvg {
+    width,
+    height,
+    cell_size,
+    padding: padding_px,
+    rows: grid_height_u32,
+    fills: Vec::new(),
+    walls,
+    overlays: Vec::new(),
+    style: style.clone(),
+  })
+}
+
+// drawn in the svg's solution color and width, with any start and end markers
+pub fn draw_solution(mut grid_svg: GridSvg, solution: &[GridCoords]) -> GridSvg {
+  let cell_size = grid_svg.cell_size;
+  let points: Vec<String> = solution.iter().map(|coords| {
+    let (x, y) = get_origin(grid_svg.padding, cell_size, grid_svg.rows, coords);
+    format!("{},{}", x + cell_size / 2, y + cell_size / 2)
+  }).collect();
+  let color = rgb(grid_svg.style.solution_color());
+  grid_svg.overlays.push(format!(r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#, points.join(" "), color, grid_svg.style.solution_width()));
+
+  // a square half the size of the cell in the middle of it
+  let markers = vec![
+    (solution.first(), grid_svg.style.start_marker()),
+    (solution.last(), grid_svg.style.end_marker()),
+  ];
+  for (coords, marker) in markers {
+    if let (Some(coords), Some(marker)) = (coords, marker) {
+      let (x, y) = get_origin(grid_svg.padding, cell_size, grid_svg.rows, coords);
+      grid_svg.overlays.push(format!(r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}"/>"#, x + cell_size / 4, y + cell_size / 4, cell_size / 2, rgb(marker)));
+    }
+  }
+  grid_svg
+}
+
+pub fn draw_distance_gradation(mut grid_svg: GridSvg, max_distance: u32, distances: &HashMap<GridCoords, u32>, color: image::Rgb<u8>) -> GridSvg {
+  // sorted so the same distances always give the same svg
+  let mut cells: Vec<(&GridCoords, &u32)> = distances.iter().collect();
+  cells.sort();
+  for (coords, distance) in cells {
+    let graded_color = modify_color_by_distance(max_distance, *distance, color);
+    let (x, y) = get_origin(grid_svg.padding, grid_svg.cell_size, grid_svg.rows, coords);
+    grid_svg.fills.push(format!(r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}"/>"#, x, y, grid_svg.cell_size, rgb(graded_color)));
+  }
+  grid_svg
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use super::{Grid, GridCoords};
+
+// what to show inside the cells of a box drawing
+#[derive(Debug, Clone, Copy)]
+pub enum Overlay<'a> {
+  // distances in base 36, e.g. from `Dijkstra.distances`
+  Distances(&'a HashMap<GridCoords, u32>),
+  // a dot in every cell along the path
+  Path(&'a [GridCoords]),
+}
+
+// whether there's a wall along horizontal line `line` over column
+// `col_index`, line 0 runs along the bottom of the grid and line `height`
+// along the top. a grid with no rows is all wall, like `Display`
+fn horizontal_wall(grid: &Grid, line: usize, col_index: usize) -> bool {
+  let above = GridCoords {
+    col_index,
+    row_index: line
+  };
+  let below = line.checked_sub(1).map(|row_index| GridCoords {
+    col_index,
+    row_index
+  });
+  match below {
+    Some(below) if line == grid.height => grid.has_wall(&below, grid.north_of(&below).as_ref()),
+    Some(below) => grid.has_wall(&above, Some(&below)),
+    None if line == grid.height => true,
+    None => grid.has_wall(&above, grid.south_of(&above).as_ref())
+  }
+}
+
+// whether there's a wall along vertical line `line` beside row `row_index`,
+// line 0 runs down the west side of the grid and line `width` the east
+fn vertical_wall(grid: &Grid, row_index: usize, line: usize) -> bool {
+  let east = GridCoords {
+    col_index: line,
+    row_index
+  };
+  let west = line.checked_sub(1).map(|col_index| GridCoords {
+    col_index,
+    row_index
+  });
+  match west {
+    Some(west) if line == grid.width => grid.has_wall(&west, grid.east_of(&west).as_ref()),
+    Some(west) => grid.has_wall(&east, Some(&west)),
+    None if line == grid.width => true,
+    None => grid.has_wall(&east, grid.west_of(&east).as_ref())
+  }
+}
+
+// which walls meet where horizontal line `line` crosses vertical line
+// `col_line`, as up, right, down, left
+fn junction(grid: &Grid, line: usize, col_line: usize) -> [bool; 4] {
+  [
+    line < grid.height && vertical_wall(grid, line, col_line),
+    col_line < grid.width && horizontal_wall(grid, line, col_line),
+    line > 0 && vertical_wall(grid, line - 1, col_line),
+    col_line > 0 && horizontal_wall(grid, line, col_line - 1),
+  ]
+}
+
+fn junction_glyph(walls: [bool; 4]) -> char {
+  match walls {
+    [false, false, false, false] => ' ',
+    [true, false, false, false] => '╵',
+    [false, true, false, false] => '╶',
+    [false, false, true, false] => '╷',
+    [false, false, false, true] => '╴',
+    [true, false, true, false] => '│',
+    [false, true, false, true] => '─',
+    [false, true, true, false] => '┌',
+    [false, false, true, true] => '┐',
+    [true, true, false, false] => '└',
+    [true, false, false, true] => '┘',
+    [true, true, true, false] => '├',
+    [false, true, true, true] => '┬',
+    [true, false, true, true] => '┤',
+    [true, true, false, true] => '┴',
+    [true, true, true, true] => '┼',
+  }
+}
+
+fn to_base36(mut n: u32) -> String {
+  let mut digits = Vec::new();
+  loop {
+    digits.push(std::char::from_digit(n % 36, 36).unwrap());
+    n /= 36;
+    if n == 0 {
+      break;
+    }
+  }
+  digits.iter().rev().collect()
+}
+
+// three characters to fill the inside of a cell
+fn cell_text(coords: &GridCoords, overlay: &Option<Overlay>) -> String {
+  match overlay {
+    Some(Overlay::Distances(distances)) => match distances.get(coords) {
+      // too far to fit
+      Some(distance) if *distance >= 36 * 36 * 36 => String::from("###"),
+      Some(distance) => format!("{:^3}", to_base36(*distance)),
+      None => String::from("   ")
+    },
+    Some(Overlay::Path(path)) if path.contains(coords) => String::from(" • "),
+    _ => String::from("   ")
+  }
+}
+
+// the grid drawn with box drawing characters, three characters across a cell
+// like `Display`, with whatever the overlay has inside the cells
+pub fn to_box_drawing(grid: &Grid, overlay: Option<Overlay>) -> String {
+  let mut lines = Vec::new();
+  for line in (0..=grid.height).rev() {
+    let mut walls = String::new();
+    for col_line in 0..=grid.width {
+      walls.push(junction_glyph(junction(grid, line, col_line)));
+      if col_line < grid.width {
+        let segment = if horizontal_wall(grid, line, col_line) { "───" } else { "   " };
+        walls.push_str(segment);
+      }
+    }
+    lines.push(walls);
+
+    let row_index = match line.checked_sub(1) {
+      Some(row_index) => row_index,
+      None => break
+    };
+    let mut cells = String::new();
+    for col_line in 0..=grid.width {
+      cells.push(if vertical_wall(grid, row_index, col_line) { '│' } else { ' ' });
+      if col_line < grid.width {
+        cells.push_str(&cell_text(&GridCoords {
+          col_index: col_line,
+          row_index
+        }, &overlay));
+      }
+    }
+    lines.push(cells);
+  }
+  lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+// quadrant characters indexed by which quarters are filled,
+// top left 1, top right 2, bottom left 4, bottom right 8
+const QUADRANTS: [char; 16] = [' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█'];
+
+// the grid as a picture two pixels a cell each way, walls and wall corners
+// filled in, top row first. pixels at odd positions are inside cells and
+// only filled when the cell is masked out
+fn pixel(grid: &Grid, py: usize, px: usize) -> bool {
+  let line = grid.height - py / 2;
+  match (py % 2, px % 2) {
+    (0, 0) => junction(grid, line, px / 2).iter().any(|wall| *wall),
+    (0, _) => horizontal_wall(grid, line, px / 2),
+    (_, 0) => vertical_wall(grid, grid.height - 1 - py / 2, px / 2),
+    _ => grid.cell_at(&GridCoords {
+      col_index: px / 2,
+      row_index: grid.height - 1 - py / 2
+    }).is_none(),
+  }
+}
+
+// the compact drawing, one character a cell plus a line along the top and
+// left edges. each cell sits in the top left quarter of its character, with
+// its east wall top right, south wall bottom left and wall corner bottom right
+pub fn to_half_blocks(grid: &Grid) -> String {
+  let (pixel_height, pixel_width) = (grid.height * 2 + 1, grid.width * 2 + 1);
+  let filled = |py: usize, px: usize| py < pixel_height && px < pixel_width && pixel(grid, py, px);
+  let mut text = String::new();
+  for cy in 0..=grid.height {
+    for cx in 0..=grid.width {
+      let mut quarters = 0;
+      // the character's top row and left column are off the picture at the edges
+      if cy > 0 && cx > 0 && filled(cy * 2 - 1, cx * 2 - 1) {
+        quarters |= 1;
+      }
+      if cy > 0 && filled(cy * 2 - 1, cx * 2) {
+        quarters |= 2;
+      }
+      if cx > 0 && filled(cy * 2, cx * 2 - 1) {
+        quarters |= 4;
+      }
+      if filled(cy * 2, cx * 2) {
+        quarters |= 8;
+      }
+      text.push(QUADRANTS[quarters]);
+    }
+    text.push('\n');
+  }
+  text
+}
@@ -0,0 +1,102 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use rand::Rng;
+
+// the shape of a grid: which cells exist, which are next to each other and
+// which are linked. generators and solvers written against this work for
+// any shape of grid, a new shape only has to implement the required methods
+pub trait Topology {
+  type Coords: Clone + Eq + Hash + Ord + Debug;
+
+  // every cell, always in the same order so seeded generation is repeatable
+  fn coords(&self) -> Vec<Self::Coords>;
+
+  fn neighbor_coords(&self, coords: &Self::Coords) -> Vec<Self::Coords>;
+
+  fn linked_coords(&self, coords: &Self::Coords) -> Vec<Self::Coords>;
+
+  fn link_bidi(&mut self, source: &Self::Coords, destination: &Self::Coords);
+
+  fn unlink_bidi(&mut self, source: &Self::Coords, destination: &Self::Coords);
+
+  fn is_linked(&self, source: &Self::Coords, destination: &Self::Coords) -> bool {
+    self.linked_coords(source).contains(destination)
+  }
+
+  // what it costs to step from `source` into the linked `destination`,
+  // every step costs the same unless a grid has weights
+  fn cost(&self, _source: &Self::Coords, _destination: &Self::Coords) -> u32 {
+    1
+  }
+
+  fn rand_coords<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Self::Coords> {
+    let coords = self.coords();
+    if coords.is_empty() {
+      return None;
+    }
+    let idx = rng.gen_range(0, coords.len());
+    Some(coords[idx].clone())
+  }
+}
+
+// implements `Topology` for a grid of cells keyed by `GridCoords`, or by
+// `$coords` when given, going through the grid's own `coords`, `cell_at`,
+// `neighbors`, `links`, `is_linked`, `link_bidi` and `unlink_bidi`. a
+// `weighted` grid goes through its own `cost` too
+macro_rules! impl_topology {
+  ($grid:ident) => {
+    impl_topology!($grid, $crate::grid::cell::GridCoords);
+  };
+  ($grid:ident, weighted) => {
+    impl_topology!($grid, $crate::grid::cell::GridCoords, weighted);
+  };
+  ($grid:ident, $coords:ty, weighted) => {
+    impl_topology!(@impl $grid, $coords, {
+      fn cost(&self, source: &Self::Coords, destination: &Self::Coords) -> u32 {
+        $grid::cost(self, source, destination)
+      }
+    });
+  };
+  ($grid:ident, $coords:ty) => {
+    impl_topology!(@impl $grid, $coords, {});
+  };
+  (@impl $grid:ident, $coords:ty, { $($extra:tt)* }) => {
+    impl $crate::grid::topology::Topology for $grid {
+      type Coords = $coords;
+
+      fn coords(&self) -> Vec<Self::Coords> {
+        $grid::coords(self)
+      }
+
+      fn neighbor_coords(&self, coords: &Self::Coords) -> Vec<Self::Coords> {
+        match self.cell_at(coords) {
+          Some(cell) => self.neighbors(cell).to_vec().into_iter().map(|neighbor| neighbor.coords().clone()).collect(),
+          None => Vec::new()
+        }
+      }
+
+      fn linked_coords(&self, coords: &Self::Coords) -> Vec<Self::Coords> {
+        match self.cell_at(coords) {
+          Some(cell) => self.links(cell).into_iter().map(|linked| linked.coords().clone()).collect(),
+          None => Vec::new()
+        }
+      }
+
+      fn link_bidi(&mut self, source: &Self::Coords, destination: &Self::Coords) {
+        $grid::link_bidi(self, source, destination)
+      }
+
+      fn unlink_bidi(&mut self, source: &Self::Coords, destination: &Self::Coords) {
+        $grid::unlink_bidi(self, source, destination)
+      }
+
+      fn is_linked(&self, source: &Self::Coords, destination: &Self::Coords) -> bool {
+        $grid::is_linked(self, source, destination)
+      }
+
+      $($extra)*
+    }
+  };
+}
+
+pub(crate) use impl_topology;
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use gust::Graph;
+use rand::Rng;
+use super::Neighbors;
+use super::cell::*;
+use super::error::GridError;
+use super::img::*;
+use super::links::impl_links;
+use super::topology::impl_topology;
+
+// rows of alternating triangles, rows count up from the bottom like `Grid`.
+// a cell whose row and column add up to an even number points up and shares
+// its base with the cell below it, the rest point down and share their base
+// with the cell above
+#[derive(Debug)]
+pub struct TriangleGrid {
+  height: usize,
+  width: usize,
+  cells: HashMap<GridCoords, Cell>,
+  graph: Graph<Cell>,
+}
+
+impl TriangleGrid {
+  pub fn new(height: usize, width: usize) -> TriangleGrid {
+    let mut grid = TriangleGrid {
+      height,
+      width,
+      cells: HashMap::new(),
+      graph: Graph::new(),
+    };
+    for coords in grid.coords() {
+      let cell = Cell::new(&coords);
+      grid.cells.insert(coords, cell);
+    }
+    grid
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn cells(&self) -> &HashMap<GridCoords, Cell> {
+    &self.cells
+  }
+
+  pub fn cell_at(&self, coords: &GridCoords) -> Option<&Cell> {
+    self.cells.get(coords)
+  }
+
+  pub fn coords(&self) -> Vec<GridCoords> {
+    let mut coords = Vec::new();
+    for col_index in 0..self.width {
+      for row_index in 0..self.height {
+        coords.push(GridCoords {
+          col_index,
+          row_index
+        });
+      }
+    }
+    coords
+  }
+
+  pub fn rand_cell(&self) -> &Cell {
+    self.rand_cell_with_rng(&mut rand::thread_rng())
+  }
+
+  pub fn rand_cell_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> &Cell {
+    let row_index = rng.gen_range(0, self.height);
+    let col_index = rng.gen_range(0, self.width);
+    self.cell_at(&GridCoords {
+      col_index,
+      row_index
+    }).unwrap()
+  }
+
+  pub fn is_upright(&self, cell: &Cell) -> bool {
+    let coords = cell.coords();
+    (coords.row_index + coords.col_index) % 2 == 0
+  }
+
+  // only cells pointing down have a neighbor to the north
+  pub fn north(&self, cell: &Cell) -> Option<&Cell> {
+    if self.is_upright(cell) {
+      return None;
+    }
+    cell.north_coords().and_then(|coords| self.cell_at(&coords))
+  }
+
+  pub fn east(&self, cell: &Cell) -> Option<&Cell> {
+    cell.east_coords().and_then(|coords| self.cell_at(&coords))
+  }
+
+  // only cells pointing up have a neighbor to the south
+  pub fn south(&self, cell: &Cell) -> Option<&Cell> {
+    if !self.is_upright(cell) {
+      return None;
+    }
+    cell.south_coords().and_then(|coords| self.cell_at(&coords))
+  }
+
+  pub fn west(&self, cell: &Cell) -> Option<&Cell> {
+    cell.west_coords().and_then(|coords| self.cell_at(&coords))
+  }
+
+  pub fn neighbors(&self, cell: &Cell) -> Neighbors<'_> {
+    Neighbors {
+      north: self.north(cell),
+      east: self.east(cell),
+      south: self.south(cell),
+      west: self.west(cell),
+    }
+  }
+
+  pub fn to_img(&self, path: &str, cell_size: u32) -> GridImage {
+    match self.try_to_img(path, cell_size) {
+      Ok(grid_image) => grid_image,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  // like `to_img` but returns an error rather than panicking, nothing is
+  // drawn if the path's extension can't be written
+  pub fn try_to_img(&self, path: &str, cell_size: u32) -> Result<GridImage, GridError> {
+    check_extension(path.as_ref())?;
+    let grid_image = try_triangle_to_img(self, cell_size)?;
+    grid_image.save(path)?;
+    Ok(grid_image)
+  }
+}
+
+impl_links!(TriangleGrid, Cell, GridCoords, walls);
+
+impl_topology!(TriangleGrid);
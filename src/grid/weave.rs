@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use super::Grid;
+use super::cell::*;
+use super::error::GridError;
+use super::img::*;
+use super::topology::Topology;
+
+// a cell on the surface of the maze, or a tunnel cell underneath it
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum WeaveCoords {
+  Over(GridCoords),
+  Under(GridCoords),
+}
+
+// the direction a tunnel runs in
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Passage {
+  NorthSouth,
+  EastWest,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+  North,
+  East,
+  South,
+  West,
+}
+
+impl Direction {
+  fn passage(self) -> Passage {
+    match self {
+      Direction::North | Direction::South => Passage::NorthSouth,
+      Direction::East | Direction::West => Passage::EastWest,
+    }
+  }
+}
+
+const DIRECTIONS: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+// a grid where a passage can duck under a straight corridor in the next cell
+// and come up on the far side. the surface is an ordinary `Grid`, and every
+// tunnel adds an under cell beneath the corridor it crosses
+#[derive(Debug)]
+pub struct WeaveGrid {
+  grid: Grid,
+  unders: BTreeMap<GridCoords, Passage>,
+}
+
+impl WeaveGrid {
+  pub fn new(height: usize, width: usize) -> WeaveGrid {
+    WeaveGrid {
+      grid: Grid::new(height, width),
+      unders: BTreeMap::new(),
+    }
+  }
+
+  // the surface cells and the links between them
+  pub fn grid(&self) -> &Grid {
+    &self.grid
+  }
+
+  pub fn height(&self) -> usize {
+    self.grid.height()
+  }
+
+  pub fn width(&self) -> usize {
+    self.grid.width()
+  }
+
+  // the tunnel running under `coords`, if there is one
+  pub fn under_at(&self, coords: &GridCoords) -> Option<Passage> {
+    self.unders.get(coords).cloned()
+  }
+
+  fn step(&self, coords: &GridCoords, direction: Direction) -> Option<GridCoords> {
+    let cell = self.grid.cell_at(coords)?;
+    let next = match direction {
+      Direction::North => self.grid.north(cell),
+      Direction::East => self.grid.east(cell),
+      Direction::South => self.grid.south(cell),
+      Direction::West => self.grid.west(cell),
+    };
+    next.map(|next| *next.coords())
+  }
+
+  fn linked_towards(&self, coords: &GridCoords, direction: Direction) -> bool {
+    match self.step(coords, direction) {
+      Some(next) => self.grid.is_linked(coords, &next),
+      None => false
+    }
+  }
+
+  // a tunnel can only cross a straight corridor that runs the other way, one
+  // that's linked at both ends and closed on both sides the tunnel runs to.
+  // only one tunnel fits under each cell
+  pub fn can_tunnel_under(&self, coords: &GridCoords, passage: Passage) -> bool {
+    if self.grid.cell_at(coords).is_none() || self.unders.contains_key(coords) {
+      return false;
+    }
+    let (corridor, sides) = match passage {
+      Passage::EastWest => ([Direction::North, Direction::South], [Direction::East, Direction::West]),
+      Passage::NorthSouth => ([Direction::East, Direction::West], [Direction::North, Direction::South]),
+    };
+    corridor.iter().all(|direction| self.linked_towards(coords, *direction))
+      && !sides.iter().any(|direction| self.linked_towards(coords, *direction))
+  }
+
+  // whether the surface of `coords` is closed heading `direction`, because
+  // a tunnel runs under it that way
+  fn crosses_tunnel(&self, coords: &GridCoords, direction: Direction) -> bool {
+    self.unders.get(coords) == Some(&direction.passage())
+  }
+
+  // the surface cell next to `coords` heading `direction`, if the surface is
+  // open between them
+  fn surface_step(&self, coords: &GridCoords, direction: Direction) -> Option<GridCoords> {
+    let next = self.step(coords, direction)?;
+    if self.crosses_tunnel(coords, direction) || self.crosses_tunnel(&next, direction) {
+      return None;
+    }
+    Some(next)
+  }
+
+  // the cell at the far end of a tunnel dug from `coords` heading `direction`,
+  // if one can be dug, along with the cell it goes under
+  fn tunnel_step(&self, coords: &GridCoords, direction: Direction) -> Option<(GridCoords, GridCoords)> {
+    let middle = self.step(coords, direction)?;
+    let far = self.step(&middle, direction)?;
+    if !self.can_tunnel_under(&middle, direction.passage()) || self.crosses_tunnel(coords, direction) || self.crosses_tunnel(&far, direction) {
+      return None;
+    }
+    Some((middle, far))
+  }
+
+  // which sides of the surface cell at `coords` open up, either straight into
+  // the next cell or through a tunnel under it, as north, east, south, west
+  pub(crate) fn open_sides(&self, coords: &GridCoords) -> [bool; 4] {
+    let mut sides = [false; 4];
+    for (side, direction) in sides.iter_mut().zip(DIRECTIONS.iter()) {
+      *side = match self.step(coords, *direction) {
+        Some(next) => self.grid.is_linked(coords, &next) || self.tunnel_from(&next, *direction),
+        None => false
+      };
+    }
+    sides
+  }
+
+  // whether a tunnel leaves through the far side of `middle` heading `direction`
+  fn tunnel_from(&self, middle: &GridCoords, direction: Direction) -> bool {
+    self.unders.get(middle) == Some(&direction.passage()) && self.step(middle, direction).is_some()
+  }
+
+  // the cell two steps away and the cell in between, if they're in a straight line
+  fn tunnel_between(&self, source: &GridCoords, destination: &GridCoords) -> Option<(GridCoords, Direction)> {
+    for direction in DIRECTIONS.iter() {
+      if let Some(middle) = self.step(source, *direction) {
+        if self.step(&middle, *direction).as_ref() == Some(destination) {
+          return Some((middle, *direction));
+        }
+      }
+    }
+    None
+  }
+
+  // linking two surface cells two apart digs a tunnel under the cell between
+  // them. false if the link was refused, because it would cross a tunnel or
+  // `can_tunnel_under` doesn't allow the tunnel. an existing tunnel is never
+  // replaced
+  pub fn link_bidi(&mut self, source: &WeaveCoords, destination: &WeaveCoords) -> bool {
+    let (source, destination) = match (source, destination) {
+      (WeaveCoords::Over(source), WeaveCoords::Over(destination)) => (source, destination),
+      _ => return false
+    };
+    if let Some((middle, direction)) = self.tunnel_between(source, destination) {
+      if self.tunnel_step(source, direction).is_none() {
+        return false;
+      }
+      self.unders.insert(middle, direction.passage());
+      return true;
+    }
+    let crossing = DIRECTIONS.iter().any(|direction| {
+      self.step(source, *direction).as_ref() == Some(destination) && self.surface_step(source, *direction).is_none()
+    });
+    if crossing {
+      return false;
+    }
+    self.grid.link_bidi(source, destination);
+    true
+  }
+
+  pub fn to_img(&self, path: &str, cell_size: u32, inset: f64) -> GridImage {
+    match self.try_to_img(path, cell_size, inset) {
+      Ok(grid_image) => grid_image,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  // like `to_img` but returns an error rather than panicking, nothing is
+  // drawn if the path's extension can't be written
+  pub fn try_to_img(&self, path: &str, cell_size: u32, inset: f64) -> Result<GridImage, GridError> {
+    check_extension(path.as_ref())?;
+    let grid_image = try_weave_to_img(self, cell_size, inset)?;
+    grid_image.save(path)?;
+    Ok(grid_image)
+  }
+}
+
+impl Topology for WeaveGrid {
+  type Coords = WeaveCoords;
+
+  // the surface cells first, then any tunnels
+  fn coords(&self) -> Vec<WeaveCoords> {
+    let mut coords: Vec<WeaveCoords> = self.grid.coords().into_iter().map(WeaveCoords::Over).collect();
+    coords.extend(self.unders.keys().cloned().map(WeaveCoords::Under));
+    coords
+  }
+
+  // surface cells neighbor the cells next to them, unless a tunnel runs
+  // under either one that way, and the cells on the far side of any corridor
+  // they could tunnel under. tunnels are carved by linking the two surface
+  // cells at either end, so under cells have no neighbors
+  fn neighbor_coords(&self, coords: &WeaveCoords) -> Vec<WeaveCoords> {
+    let coords = match coords {
+      WeaveCoords::Over(coords) => coords,
+      WeaveCoords::Under(_) => return Vec::new()
+    };
+    let mut neighbors = Vec::new();
+    for direction in DIRECTIONS.iter() {
+      if let Some(next) = self.surface_step(coords, *direction) {
+        neighbors.push(WeaveCoords::Over(next));
+      }
+      if let Some((_, far)) = self.tunnel_step(coords, *direction) {
+        neighbors.push(WeaveCoords::Over(far));
+      }
+    }
+    neighbors
+  }
+
+  fn linked_coords(&self, coords: &WeaveCoords) -> Vec<WeaveCoords> {
+    match coords {
+      WeaveCoords::Over(coords) => {
+        let mut linked: Vec<WeaveCoords> = match self.grid.cell_at(coords) {
+          Some(cell) => self.grid.links(cell).into_iter().map(|linked| WeaveCoords::Over(*linked.coords())).collect(),
+          None => Vec::new()
+        };
+        for direction in DIRECTIONS.iter() {
+          if let Some(middle) = self.step(coords, *direction) {
+            if self.tunnel_from(&middle, *direction) {
+              linked.push(WeaveCoords::Under(middle));
+            }
+          }
+        }
+        linked
+      }
+      WeaveCoords::Under(coords) => {
+        let ends = match self.unders.get(coords) {
+          Some(Passage::NorthSouth) => [Direction::North, Direction::South],
+          Some(Passage::EastWest) => [Direction::East, Direction::West],
+          None => return Vec::new()
+        };
+        ends.iter().filter_map(|direction| self.step(coords, *direction)).map(WeaveCoords::Over).collect()
+      }
+    }
+  }
+
+  fn link_bidi(&mut self, source: &WeaveCoords, destination: &WeaveCoords) {
+    WeaveGrid::link_bidi(self, source, destination);
+  }
+
+  fn unlink_bidi(&mut self, source: &WeaveCoords, destination: &WeaveCoords) {
+    match (source, destination) {
+      (WeaveCoords::Over(source), WeaveCoords::Over(destination)) => {
+        match self.tunnel_between(source, destination) {
+          Some((middle, _)) => {
+            self.unders.remove(&middle);
+          }
+          None => {
+            self.grid.unlink_bidi(source, destination);
+          }
+        }
+      }
+      (WeaveCoords::Under(middle), _) | (_, WeaveCoords::Under(middle)) => {
+        // a tunnel can't be half open, so it goes completely
+        self.unders.remove(middle);
+      }
+    }
+  }
+}
@@ -4,5 +4,13 @@ pub mod mazes;
 pub mod prelude {
   pub use super::grid::*;
   pub use super::grid::cell::*;
+  pub use super::grid::error::*;
+  pub use super::grid::grid3d::*;
+  pub use super::grid::hex::*;
+  pub use super::grid::mask::*;
+  pub use super::grid::polar::*;
+  pub use super::grid::topology::*;
+  pub use super::grid::triangle::*;
+  pub use super::grid::weave::*;
   pub use super::mazes::*;
 }
@@ -0,0 +1,39 @@
+use crate::grid::topology::Topology;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::{assert_connected, RandomSet};
+
+pub fn apply_to<G: Topology>(grid: G) -> G {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+// panics if some cells can't be reached from the rest
+pub fn apply_to_with_rng<G: Topology, R: Rng + ?Sized>(mut grid: G, rng: &mut R) -> G {
+  assert_connected(&grid, "Aldous-Broder");
+  let mut unvisited = RandomSet::new(grid.coords());
+  carve(&mut grid, rng, &mut unvisited, 0);
+  grid
+}
+
+// random walk from a random cell, linking every time the walk steps into an
+// unvisited cell, until only `remaining` cells are left unvisited
+pub(super) fn carve<G: Topology, R: Rng + ?Sized>(grid: &mut G, rng: &mut R, unvisited: &mut RandomSet<G::Coords>, remaining: usize) {
+  let mut current = match grid.rand_coords(rng) {
+    Some(coords) => coords,
+    None => return
+  };
+  unvisited.remove(&current);
+  while unvisited.len() > remaining {
+    let next = match grid.neighbor_coords(&current).choose(rng) {
+      Some(neighbor) => neighbor.clone(),
+      None => {
+        // a lone cell has nowhere to walk
+        return;
+      }
+    };
+    if unvisited.remove(&next) {
+      grid.link_bidi(&current, &next);
+    }
+    current = next;
+  }
+}
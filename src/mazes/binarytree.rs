@@ -1,13 +1,22 @@
 use crate::grid::Grid;
 use rand::Rng;
-use super::CoinFlip;
+use super::{CoinFlip, Seams};
 
-pub fn apply_to(mut grid: Grid) -> Grid {
+pub fn apply_to(grid: Grid) -> Grid {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+// panics on a masked grid, a cell with a gap to the north and east would
+// be cut off
+pub fn apply_to_with_rng<R: Rng + ?Sized>(mut grid: Grid, rng: &mut R) -> Grid {
+  assert!(!grid.is_masked(), "binary tree needs every cell, the grid is masked");
+  let seams = Seams::new(&grid, rng);
   for coords in grid.coords() {
     let cell = grid.cell_at(&coords).unwrap();
-    let north = grid.north(cell);
-    let east = grid.east(cell);
-    match rand::thread_rng().gen::<CoinFlip>() {
+    // carving across the cut in a wrapping grid would close a loop
+    let north = grid.north(cell).filter(|_| !seams.is_north_edge(&coords));
+    let east = grid.east(cell).filter(|_| !seams.is_east_edge(&coords));
+    match rng.gen::<CoinFlip>() {
       CoinFlip::Heads => {
         // try north first
         if let Some(north) = north {
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use crate::grid::Grid;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::{CoinFlip, Seams};
+
+// the links carved for a single row. `east[i]` links column `i` to column
+// `i + 1`, `north[i]` links column `i` to the same column in the next row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+  pub row_index: usize,
+  pub east: Vec<bool>,
+  pub north: Vec<bool>,
+}
+
+// generates a maze one row at a time, bottom to top, only ever holding the
+// set membership of the current row
+#[derive(Debug)]
+pub struct Rows<R: Rng> {
+  rng: R,
+  height: Option<usize>,
+  row_index: usize,
+  sets: Vec<usize>,
+  next_set: usize,
+  done: bool,
+}
+
+impl<R: Rng> Rows<R> {
+  // never ends, use `last_row` to close the maze off
+  pub fn new(width: usize, rng: R) -> Rows<R> {
+    let mut rows = Rows {
+      rng,
+      height: None,
+      row_index: 0,
+      sets: Vec::with_capacity(width),
+      next_set: 0,
+      done: false,
+    };
+    for _ in 0..width {
+      let set = rows.fresh_set();
+      rows.sets.push(set);
+    }
+    rows
+  }
+
+  pub fn with_height(width: usize, height: usize, rng: R) -> Rows<R> {
+    let mut rows = Rows::new(width, rng);
+    rows.height = Some(height);
+    rows.done = height == 0;
+    rows
+  }
+
+  // carves the final row, joining every remaining set so the maze is whole
+  pub fn last_row(&mut self) -> Row {
+    self.done = true;
+    self.carve(true)
+  }
+
+  fn fresh_set(&mut self) -> usize {
+    let set = self.next_set;
+    self.next_set += 1;
+    set
+  }
+
+  fn merge(&mut self, from: usize, into: usize) {
+    for set in self.sets.iter_mut() {
+      if *set == from {
+        *set = into;
+      }
+    }
+  }
+
+  fn carve(&mut self, last: bool) -> Row {
+    let width = self.sets.len();
+    let mut east = vec![false; width.saturating_sub(1)];
+    for col in 1..width {
+      let (west_set, set) = (self.sets[col - 1], self.sets[col]);
+      if west_set == set {
+        continue;
+      }
+      if last || self.rng.gen::<CoinFlip>() == CoinFlip::Heads {
+        east[col - 1] = true;
+        self.merge(set, west_set);
+      }
+    }
+
+    let mut north = vec![false; width];
+    if !last {
+      // every set has to carry on into the next row at least once
+      let mut order = Vec::new();
+      let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+      for (col, set) in self.sets.iter().enumerate() {
+        members.entry(*set).or_insert_with(|| {
+          order.push(*set);
+          Vec::new()
+        }).push(col);
+      }
+      for set in order {
+        let mut cols = members.remove(&set).unwrap();
+        cols.shuffle(&mut self.rng);
+        let count = self.rng.gen_range(1, cols.len() + 1);
+        for col in cols.into_iter().take(count) {
+          north[col] = true;
+        }
+      }
+      for (col, carried) in north.iter().enumerate() {
+        if !carried {
+          self.sets[col] = self.fresh_set();
+        }
+      }
+    }
+
+    let row = Row {
+      row_index: self.row_index,
+      east,
+      north,
+    };
+    self.row_index += 1;
+    row
+  }
+}
+
+impl<R: Rng> Iterator for Rows<R> {
+  type Item = Row;
+
+  fn next(&mut self) -> Option<Row> {
+    if self.done {
+      return None;
+    }
+    let last = match self.height {
+      Some(height) => self.row_index + 1 >= height,
+      None => false,
+    };
+    if last {
+      self.done = true;
+    }
+    Some(self.carve(last))
+  }
+}
+
+pub fn apply_to(grid: Grid) -> Grid {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+// panics on a masked grid, the rows would be joined across the gaps
+pub fn apply_to_with_rng<R: Rng + ?Sized>(mut grid: Grid, rng: &mut R) -> Grid {
+  assert!(!grid.is_masked(), "Eller's needs every cell, the grid is masked");
+  let seams = Seams::new(&grid, rng);
+  let rows = seams.rows(&grid);
+  let carved = Rows::with_height(grid.width(), grid.height(), rng);
+  for (row, links) in rows.into_iter().zip(carved) {
+    for coords in row {
+      // look links up by column, the row is unrolled from the seams
+      let col_index = seams.unroll(&coords).col_index;
+      let (east, north) = {
+        let cell = grid.cell_at(&coords).unwrap();
+        (grid.east(cell).map(|east| *east.coords()), grid.north(cell).map(|north| *north.coords()))
+      };
+      if let Some(east) = east {
+        if !seams.is_east_edge(&coords) && links.east[col_index] {
+          grid.link_bidi(&coords, &east);
+        }
+      }
+      if let Some(north) = north {
+        if links.north[col_index] {
+          grid.link_bidi(&coords, &north);
+        }
+      }
+    }
+  }
+  grid
+}
@@ -0,0 +1,123 @@
+use crate::grid::cell::GridCoords;
+use crate::grid::topology::Topology;
+use rand::{Rng, RngCore};
+use rand::seq::SliceRandom;
+
+// picks which active cell the maze grows from next, `active` is never empty
+// and is ordered oldest first
+pub trait Selector<C = GridCoords> {
+  fn select(&mut self, active: &[C], rng: &mut dyn RngCore) -> usize;
+}
+
+// behaves like the recursive backtracker
+#[derive(Debug, Clone, Copy)]
+pub struct Newest;
+
+impl<C> Selector<C> for Newest {
+  fn select(&mut self, active: &[C], _rng: &mut dyn RngCore) -> usize {
+    active.len() - 1
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Oldest;
+
+impl<C> Selector<C> for Oldest {
+  fn select(&mut self, _active: &[C], _rng: &mut dyn RngCore) -> usize {
+    0
+  }
+}
+
+// behaves like simplified Prim's
+#[derive(Debug, Clone, Copy)]
+pub struct Random;
+
+impl<C> Selector<C> for Random {
+  fn select(&mut self, active: &[C], rng: &mut dyn RngCore) -> usize {
+    rng.gen_range(0, active.len())
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Middle;
+
+impl<C> Selector<C> for Middle {
+  fn select(&mut self, active: &[C], _rng: &mut dyn RngCore) -> usize {
+    active.len() / 2
+  }
+}
+
+// delegates to one of several selectors, picked by relative weight each step,
+// e.g. `Mix::new().with(75, Newest).with(25, Random)`
+pub struct Mix<C = GridCoords> {
+  choices: Vec<(u32, Box<dyn Selector<C>>)>,
+  total_weight: u32,
+}
+
+impl<C> Default for Mix<C> {
+  fn default() -> Mix<C> {
+    Mix::new()
+  }
+}
+
+impl<C> Mix<C> {
+  pub fn new() -> Mix<C> {
+    Mix {
+      choices: Vec::new(),
+      total_weight: 0,
+    }
+  }
+
+  pub fn with<S: Selector<C> + 'static>(mut self, weight: u32, selector: S) -> Mix<C> {
+    if weight > 0 {
+      self.total_weight += weight;
+      self.choices.push((weight, Box::new(selector)));
+    }
+    self
+  }
+}
+
+impl<C> Selector<C> for Mix<C> {
+  fn select(&mut self, active: &[C], rng: &mut dyn RngCore) -> usize {
+    if self.total_weight == 0 {
+      return active.len() - 1;
+    }
+    let mut roll = rng.gen_range(0, self.total_weight);
+    for (weight, selector) in self.choices.iter_mut() {
+      if roll < *weight {
+        return selector.select(active, rng);
+      }
+      roll -= *weight;
+    }
+    unreachable!("roll is always below the total weight")
+  }
+}
+
+pub fn apply_to<G: Topology, S: Selector<G::Coords>>(grid: G, selector: S) -> G {
+  apply_to_with_rng(grid, selector, &mut rand::thread_rng())
+}
+
+pub fn apply_to_with_rng<G: Topology, S: Selector<G::Coords>, R: Rng + ?Sized>(mut grid: G, mut selector: S, mut rng: &mut R) -> G {
+  let start = match grid.rand_coords(rng) {
+    Some(start) => start,
+    None => return grid
+  };
+  let mut active = vec![start];
+  while !active.is_empty() {
+    let idx = selector.select(&active, &mut rng).min(active.len() - 1);
+    let coords = active[idx].clone();
+    let unvisited: Vec<G::Coords> = grid.neighbor_coords(&coords).into_iter()
+      .filter(|neighbor| grid.linked_coords(neighbor).is_empty())
+      .collect();
+    match unvisited.choose(rng) {
+      Some(next) => {
+        grid.link_bidi(&coords, next);
+        active.push(next.clone());
+      }
+      None => {
+        active.remove(idx);
+      }
+    }
+  }
+  grid
+}
@@ -0,0 +1,51 @@
+use crate::grid::topology::Topology;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+pub fn apply_to<G: Topology>(grid: G) -> G {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+pub fn apply_to_with_rng<G: Topology, R: Rng + ?Sized>(grid: G, rng: &mut R) -> G {
+  match grid.rand_coords(rng) {
+    Some(start) => apply_to_with_start(grid, &start, rng),
+    None => grid
+  }
+}
+
+pub fn apply_to_with_start<G: Topology, R: Rng + ?Sized>(mut grid: G, start: &G::Coords, rng: &mut R) -> G {
+  let mut current = Some(start.clone());
+  while let Some(coords) = current {
+    let unvisited: Vec<G::Coords> = grid.neighbor_coords(&coords).into_iter()
+      .filter(|neighbor| grid.linked_coords(neighbor).is_empty())
+      .collect();
+    match unvisited.choose(rng) {
+      Some(next) => {
+        // kill: keep walking
+        grid.link_bidi(&coords, next);
+        current = Some(next.clone());
+      }
+      None => {
+        // hunt: find the first unvisited cell next to the maze and join it on
+        current = hunt(&mut grid, rng);
+      }
+    }
+  }
+  grid
+}
+
+fn hunt<G: Topology, R: Rng + ?Sized>(grid: &mut G, rng: &mut R) -> Option<G::Coords> {
+  for coords in grid.coords() {
+    if !grid.linked_coords(&coords).is_empty() {
+      continue;
+    }
+    let visited: Vec<G::Coords> = grid.neighbor_coords(&coords).into_iter()
+      .filter(|neighbor| !grid.linked_coords(neighbor).is_empty())
+      .collect();
+    if let Some(neighbor) = visited.choose(rng) {
+      grid.link_bidi(&coords, neighbor);
+      return Some(coords);
+    }
+  }
+  None
+}
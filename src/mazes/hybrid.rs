@@ -0,0 +1,22 @@
+// starts out as Aldous-Broder, which is quick while most cells are
+// unvisited, then switches to Wilson's, which is quick once most are
+use crate::grid::topology::Topology;
+use rand::Rng;
+use super::{aldous_broder, assert_connected, wilsons, RandomSet};
+
+pub fn apply_to<G: Topology>(grid: G, switch_at: f64) -> G {
+  apply_to_with_rng(grid, switch_at, &mut rand::thread_rng())
+}
+
+// `switch_at` is the fraction of cells (0.0 to 1.0) visited before switching.
+// panics if some cells can't be reached from the rest
+pub fn apply_to_with_rng<G: Topology, R: Rng + ?Sized>(mut grid: G, switch_at: f64, rng: &mut R) -> G {
+  assert_connected(&grid, "hybrid");
+  let mut unvisited = RandomSet::new(grid.coords());
+  let total = unvisited.len();
+  let fraction = switch_at.max(0.0).min(1.0);
+  let visit_count = (total as f64 * fraction).ceil() as usize;
+  aldous_broder::carve(&mut grid, rng, &mut unvisited, total - visit_count.min(total));
+  wilsons::carve(&mut grid, rng, &mut unvisited);
+  grid
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::grid::cell::GridCoords;
+use crate::grid::topology::Topology;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+// disjoint sets of coords, every set is a connected piece of the maze
+#[derive(Debug, Default)]
+pub struct UnionFind<C = GridCoords> {
+  parents: HashMap<C, C>,
+  ranks: HashMap<C, usize>,
+}
+
+impl<C: Clone + Eq + Hash> UnionFind<C> {
+  pub fn new() -> UnionFind<C> {
+    UnionFind {
+      parents: HashMap::new(),
+      ranks: HashMap::new(),
+    }
+  }
+
+  pub fn find(&mut self, coords: &C) -> C {
+    let mut root = coords.clone();
+    while let Some(parent) = self.parents.get(&root) {
+      if *parent == root {
+        break;
+      }
+      root = parent.clone();
+    }
+    // point everything on the way straight at the root
+    let mut current = coords.clone();
+    while current != root {
+      let parent = self.parents.get(&current).cloned().unwrap_or_else(|| root.clone());
+      self.parents.insert(current, root.clone());
+      current = parent;
+    }
+    root
+  }
+
+  // merges the sets containing `a` and `b`, false if they were already one set
+  pub fn union(&mut self, a: &C, b: &C) -> bool {
+    let root_a = self.find(a);
+    let root_b = self.find(b);
+    if root_a == root_b {
+      return false;
+    }
+    let rank_a = self.ranks.get(&root_a).cloned().unwrap_or(0);
+    let rank_b = self.ranks.get(&root_b).cloned().unwrap_or(0);
+    if rank_a < rank_b {
+      self.parents.insert(root_a, root_b);
+    } else {
+      self.parents.insert(root_b, root_a.clone());
+      if rank_a == rank_b {
+        self.ranks.insert(root_a, rank_a + 1);
+      }
+    }
+    true
+  }
+}
+
+pub fn apply_to<G: Topology>(grid: G) -> G {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+pub fn apply_to_with_rng<G: Topology, R: Rng + ?Sized>(grid: G, rng: &mut R) -> G {
+  apply_to_with_links(grid, &[], rng)
+}
+
+// `links` are carved before generation starts, e.g. to pre-place rooms or
+// corridors, the rest of the maze is grown around them. links between cells
+// that aren't neighbors, or that would close a loop, are skipped
+pub fn apply_to_with_links<G: Topology, R: Rng + ?Sized>(mut grid: G, links: &[(G::Coords, G::Coords)], rng: &mut R) -> G {
+  let mut sets = UnionFind::new();
+  for (from, to) in links {
+    if grid.neighbor_coords(from).contains(to) && sets.union(from, to) {
+      grid.link_bidi(from, to);
+    }
+  }
+
+  let mut edges = Vec::new();
+  for coords in grid.coords() {
+    for neighbor in grid.neighbor_coords(&coords) {
+      // only take each pair of neighbors once
+      if coords < neighbor {
+        edges.push((coords.clone(), neighbor));
+      }
+    }
+  }
+  edges.shuffle(rng);
+
+  for (from, to) in edges {
+    // an edge can stop being a way through once others are carved
+    if grid.neighbor_coords(&from).contains(&to) && sets.union(&from, &to) {
+      grid.link_bidi(&from, &to);
+    }
+  }
+  grid
+}
@@ -1,10 +1,25 @@
+pub mod aldous_broder;
 pub mod binarytree;
+pub mod ellers;
+pub mod growing_tree;
+pub mod hunt_and_kill;
+pub mod hybrid;
+pub mod kruskals;
+pub mod prims;
+pub mod recursive_backtracker;
+pub mod recursive_division;
 pub mod sidewinder;
 pub mod solutions;
+pub mod wilsons;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use rand::Rng;
+use crate::grid::Grid;
+use crate::grid::cell::GridCoords;
+use crate::grid::topology::Topology;
 use rand::distributions::{Distribution, Standard};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum CoinFlip {
   Heads,
   Tails
@@ -19,3 +34,152 @@ impl Distribution<CoinFlip> for Standard {
         }
     }
 }
+
+// set of coords with constant time removal and random selection,
+// selection order only depends on the insertion order and the rng
+#[derive(Debug)]
+struct RandomSet<T> {
+  items: Vec<T>,
+  index: HashMap<T, usize>,
+}
+
+impl<T: Clone + Eq + Hash> RandomSet<T> {
+  fn new(items: Vec<T>) -> RandomSet<T> {
+    let mut set = RandomSet {
+      items: Vec::new(),
+      index: HashMap::new(),
+    };
+    for coords in items {
+      set.insert(coords);
+    }
+    set
+  }
+
+  fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  fn contains(&self, coords: &T) -> bool {
+    self.index.contains_key(coords)
+  }
+
+  fn insert(&mut self, coords: T) {
+    if !self.contains(&coords) {
+      self.index.insert(coords.clone(), self.items.len());
+      self.items.push(coords);
+    }
+  }
+
+  fn remove(&mut self, coords: &T) -> bool {
+    match self.index.remove(coords) {
+      Some(idx) => {
+        self.items.swap_remove(idx);
+        if let Some(moved) = self.items.get(idx) {
+          self.index.insert(moved.clone(), idx);
+        }
+        true
+      }
+      None => false
+    }
+  }
+
+  fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<T> {
+    if self.items.is_empty() {
+      return None;
+    }
+    let idx = rng.gen_range(0, self.items.len());
+    Some(self.items[idx].clone())
+  }
+}
+
+// the walks in Aldous-Broder and Wilson's only stop once they've reached
+// every cell, so they'd never stop on a grid split into separate pieces,
+// e.g. by a mask
+fn assert_connected<G: Topology>(grid: &G, generator: &str) {
+  let coords = grid.coords();
+  let first = match coords.first() {
+    Some(first) => first.clone(),
+    None => return
+  };
+  let mut reached = HashSet::new();
+  reached.insert(first.clone());
+  let mut frontier = vec![first];
+  while let Some(current) = frontier.pop() {
+    for neighbor in grid.neighbor_coords(&current) {
+      if reached.insert(neighbor.clone()) {
+        frontier.push(neighbor);
+      }
+    }
+  }
+  if reached.len() < coords.len() {
+    panic!("{} needs every cell to be reachable, {} of {} cells are cut off", generator, coords.len() - reached.len(), coords.len());
+  }
+}
+
+// where a wrapping grid gets cut open for the generators that carve a
+// rectangle. the cut is made along a random column, and a random row on a
+// torus, so the seam the grid wraps round gets carved like any other wall.
+// a flat grid is cut along its edges and the rng is left untouched
+#[derive(Debug, Clone, Copy)]
+struct Seams {
+  height: usize,
+  width: usize,
+  row_offset: usize,
+  col_offset: usize,
+}
+
+impl Seams {
+  fn new<R: Rng + ?Sized>(grid: &Grid, rng: &mut R) -> Seams {
+    let col_offset = if grid.wraps_east() {
+      rng.gen_range(0, grid.width())
+    } else {
+      0
+    };
+    let row_offset = if grid.wraps_north() {
+      rng.gen_range(0, grid.height())
+    } else {
+      0
+    };
+    Seams {
+      height: grid.height(),
+      width: grid.width(),
+      row_offset,
+      col_offset,
+    }
+  }
+
+  // where `coords` ends up in the cut open rectangle
+  fn unroll(&self, coords: &GridCoords) -> GridCoords {
+    GridCoords {
+      row_index: (coords.row_index + self.height - self.row_offset) % self.height,
+      col_index: (coords.col_index + self.width - self.col_offset) % self.width,
+    }
+  }
+
+  // where a position in the cut open rectangle is on the grid
+  fn roll(&self, coords: &GridCoords) -> GridCoords {
+    GridCoords {
+      row_index: (coords.row_index + self.row_offset) % self.height,
+      col_index: (coords.col_index + self.col_offset) % self.width,
+    }
+  }
+
+  fn is_north_edge(&self, coords: &GridCoords) -> bool {
+    self.unroll(coords).row_index + 1 == self.height
+  }
+
+  fn is_east_edge(&self, coords: &GridCoords) -> bool {
+    self.unroll(coords).col_index + 1 == self.width
+  }
+
+  // the grid's rows, bottom to top and west to east, as they are in the cut
+  // open rectangle
+  fn rows(&self, grid: &Grid) -> Vec<Vec<GridCoords>> {
+    let mut rows = grid.rows();
+    rows.rotate_left(self.row_offset);
+    for row in rows.iter_mut() {
+      row.sort_by_key(|coords| self.unroll(coords).col_index);
+    }
+    rows
+  }
+}
@@ -0,0 +1,72 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use crate::grid::topology::Topology;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::RandomSet;
+
+fn unvisited_neighbors<G: Topology>(grid: &G, coords: &G::Coords) -> Vec<G::Coords> {
+  grid.neighbor_coords(coords).into_iter()
+    .filter(|neighbor| grid.linked_coords(neighbor).is_empty())
+    .collect()
+}
+
+pub fn apply_simplified_to<G: Topology>(grid: G) -> G {
+  apply_simplified_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+// grows the maze from a random frontier cell each step
+pub fn apply_simplified_to_with_rng<G: Topology, R: Rng + ?Sized>(mut grid: G, rng: &mut R) -> G {
+  let start = match grid.rand_coords(rng) {
+    Some(start) => start,
+    None => return grid
+  };
+  let mut active = RandomSet::new(vec![start]);
+  while let Some(coords) = active.choose(rng) {
+    match unvisited_neighbors(&grid, &coords).choose(rng) {
+      Some(neighbor) => {
+        grid.link_bidi(&coords, neighbor);
+        active.insert(neighbor.clone());
+      }
+      None => {
+        active.remove(&coords);
+      }
+    }
+  }
+  grid
+}
+
+pub fn apply_true_to<G: Topology>(grid: G) -> G {
+  apply_true_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+// every cell gets a random weight, the maze always grows from the cheapest
+// frontier cell into its cheapest unvisited neighbor
+pub fn apply_true_to_with_rng<G: Topology, R: Rng + ?Sized>(mut grid: G, rng: &mut R) -> G {
+  let mut costs = HashMap::new();
+  for coords in grid.coords() {
+    costs.insert(coords, rng.gen_range(0, 100u32));
+  }
+  let start = match grid.rand_coords(rng) {
+    Some(start) => start,
+    None => return grid
+  };
+  // ties on cost fall back to position so a seed always gives the same maze
+  let key = |coords: G::Coords| (costs[&coords], coords);
+
+  let mut active = BinaryHeap::new();
+  active.push(Reverse(key(start)));
+  while let Some(Reverse((_, coords))) = active.peek().cloned() {
+    let cheapest = unvisited_neighbors(&grid, &coords).into_iter().map(&key).min();
+    match cheapest {
+      Some((cost, neighbor)) => {
+        grid.link_bidi(&coords, &neighbor);
+        active.push(Reverse((cost, neighbor)));
+      }
+      None => {
+        active.pop();
+      }
+    }
+  }
+  grid
+}
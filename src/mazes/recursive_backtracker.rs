@@ -0,0 +1,35 @@
+use crate::grid::topology::Topology;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+pub fn apply_to<G: Topology>(grid: G) -> G {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+pub fn apply_to_with_rng<G: Topology, R: Rng + ?Sized>(grid: G, rng: &mut R) -> G {
+  match grid.rand_coords(rng) {
+    Some(start) => apply_to_with_start(grid, &start, rng),
+    None => grid
+  }
+}
+
+// the "recursion" lives on an explicit stack so huge grids can't overflow
+pub fn apply_to_with_start<G: Topology, R: Rng + ?Sized>(mut grid: G, start: &G::Coords, rng: &mut R) -> G {
+  let mut stack = vec![start.clone()];
+  while let Some(current) = stack.last().cloned() {
+    let unvisited: Vec<G::Coords> = grid.neighbor_coords(&current).into_iter()
+      .filter(|neighbor| grid.linked_coords(neighbor).is_empty())
+      .collect();
+    match unvisited.choose(rng) {
+      Some(next) => {
+        grid.link_bidi(&current, next);
+        stack.push(next.clone());
+      }
+      None => {
+        // dead end, back up
+        stack.pop();
+      }
+    }
+  }
+  grid
+}
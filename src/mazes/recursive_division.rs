@@ -0,0 +1,133 @@
+use crate::grid::Grid;
+use crate::grid::cell::GridCoords;
+use rand::Rng;
+use super::Seams;
+
+// a rectangular region of the grid cut open along its seams,
+// `row_index`/`col_index` is its bottom left cell
+#[derive(Debug)]
+struct Region {
+  row_index: usize,
+  col_index: usize,
+  height: usize,
+  width: usize,
+}
+
+pub fn apply_to(grid: Grid) -> Grid {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+pub fn apply_to_with_rng<R: Rng + ?Sized>(grid: Grid, rng: &mut R) -> Grid {
+  apply_to_with_rooms(grid, 0, rng)
+}
+
+// regions no taller and no wider than `room_size` are left open as rooms.
+// panics on a masked grid, walls would cut off cells behind the gaps
+pub fn apply_to_with_rooms<R: Rng + ?Sized>(mut grid: Grid, room_size: usize, rng: &mut R) -> Grid {
+  assert!(!grid.is_masked(), "recursive division needs every cell, the grid is masked");
+  grid.link_all();
+  // walls are collected and unlinked all at once at the end
+  let mut walls = Vec::new();
+  // wall off the cut in a wrapping grid, every other wall is a division
+  let seams = Seams::new(&grid, rng);
+  for coords in grid.coords() {
+    let (north, east) = {
+      let cell = grid.cell_at(&coords).unwrap();
+      (grid.north(cell).map(|north| *north.coords()), grid.east(cell).map(|east| *east.coords()))
+    };
+    if let Some(north) = north.filter(|_| seams.is_north_edge(&coords)) {
+      walls.push((coords, north));
+    }
+    if let Some(east) = east.filter(|_| seams.is_east_edge(&coords)) {
+      walls.push((coords, east));
+    }
+  }
+  // regions waiting to be divided, kept on a stack rather than recursing
+  let mut regions = vec![Region {
+    row_index: 0,
+    col_index: 0,
+    height: grid.height(),
+    width: grid.width(),
+  }];
+  while let Some(region) = regions.pop() {
+    if region.height <= 1 || region.width <= 1 {
+      continue;
+    }
+    if region.height <= room_size && region.width <= room_size {
+      continue;
+    }
+    if region.height > region.width {
+      divide_horizontally(&mut walls, &seams, region, &mut regions, rng);
+    } else {
+      divide_vertically(&mut walls, &seams, region, &mut regions, rng);
+    }
+  }
+  grid.unlink_bidi_all(&walls);
+  grid
+}
+
+// adds a wall along the top of a random row of the region, with one gap in it
+fn divide_horizontally<R: Rng + ?Sized>(walls: &mut Vec<(GridCoords, GridCoords)>, seams: &Seams, region: Region, regions: &mut Vec<Region>, rng: &mut R) {
+  let below = rng.gen_range(1, region.height);
+  let passage = region.col_index + rng.gen_range(0, region.width);
+  let wall_row = region.row_index + below - 1;
+  for col_index in region.col_index..region.col_index + region.width {
+    if col_index == passage {
+      continue;
+    }
+    let south = GridCoords {
+      col_index,
+      row_index: wall_row,
+    };
+    let north = GridCoords {
+      col_index,
+      row_index: wall_row + 1,
+    };
+    walls.push((seams.roll(&south), seams.roll(&north)));
+  }
+  regions.push(Region {
+    row_index: region.row_index,
+    col_index: region.col_index,
+    height: below,
+    width: region.width,
+  });
+  regions.push(Region {
+    row_index: wall_row + 1,
+    col_index: region.col_index,
+    height: region.height - below,
+    width: region.width,
+  });
+}
+
+// adds a wall along the east side of a random column of the region, with one gap in it
+fn divide_vertically<R: Rng + ?Sized>(walls: &mut Vec<(GridCoords, GridCoords)>, seams: &Seams, region: Region, regions: &mut Vec<Region>, rng: &mut R) {
+  let west_of = rng.gen_range(1, region.width);
+  let passage = region.row_index + rng.gen_range(0, region.height);
+  let wall_col = region.col_index + west_of - 1;
+  for row_index in region.row_index..region.row_index + region.height {
+    if row_index == passage {
+      continue;
+    }
+    let west = GridCoords {
+      col_index: wall_col,
+      row_index,
+    };
+    let east = GridCoords {
+      col_index: wall_col + 1,
+      row_index,
+    };
+    walls.push((seams.roll(&west), seams.roll(&east)));
+  }
+  regions.push(Region {
+    row_index: region.row_index,
+    col_index: region.col_index,
+    height: region.height,
+    width: west_of,
+  });
+  regions.push(Region {
+    row_index: region.row_index,
+    col_index: wall_col + 1,
+    height: region.height,
+    width: region.width - west_of,
+  });
+}
@@ -1,10 +1,10 @@
 use crate::grid::Grid;
 use crate::grid::cell::GridCoords;
-use super::CoinFlip;
+use super::{CoinFlip, Seams};
 use rand::prelude::*;
 
 macro_rules! close_run {
-  ($grid:ident, $run:ident) => {
+  ($grid:ident, $seams:ident, $run:ident, $rng:ident) => {
     if $run.len() > 0 {
       for i in 1..$run.len() {
         let from = $run.get(i - 1).unwrap();
@@ -12,11 +12,10 @@ macro_rules! close_run {
 
         $grid.link_bidi(from, to);
       }
-      let mut rng = thread_rng();
-      let run_idx = rng.gen_range(0, $run.len());
+      let run_idx = $rng.gen_range(0, $run.len());
       let run_cell_coords = $run.get(run_idx).unwrap();
       // link a random cell in the run to the north
-      let north = $grid.north($grid.cell_at(run_cell_coords).unwrap());
+      let north = $grid.north($grid.cell_at(run_cell_coords).unwrap()).filter(|_| !$seams.is_north_edge(run_cell_coords));
       if let Some(north) = north {
         $grid.link_bidi(run_cell_coords, &north.coords().clone());
       }
@@ -26,14 +25,21 @@ macro_rules! close_run {
   };
 }
 
-pub fn apply_to(mut grid: Grid) -> Grid {
+pub fn apply_to(grid: Grid) -> Grid {
+  apply_to_with_rng(grid, &mut thread_rng())
+}
+
+// panics on a masked grid, runs would be carved straight across the gaps
+pub fn apply_to_with_rng<R: Rng + ?Sized>(mut grid: Grid, rng: &mut R) -> Grid {
+  assert!(!grid.is_masked(), "sidewinder needs every cell, the grid is masked");
   let top_row_idx;
   if grid.height() > 0 {
     top_row_idx = grid.height() - 1;
   } else {
     top_row_idx = grid.height();
   }
-  let rows = grid.rows();
+  let seams = Seams::new(&grid, rng);
+  let rows = seams.rows(&grid);
   let mut row_idx = 0;
   for ref row in rows {
     let mut run: Vec<&GridCoords> = Vec::new();
@@ -44,23 +50,23 @@ pub fn apply_to(mut grid: Grid) -> Grid {
       if row_idx == top_row_idx {
         continue; // top row can't close runs so just skip that part
       }
-      let east = grid.east(cell);
+      let east = grid.east(cell).filter(|_| !seams.is_east_edge(id));
       match east {
         None => {
           // nowhere left to go but up
-          close_run!(grid, run);
+          close_run!(grid, seams, run, rng);
         }
         Some(_) => {
           // could continue east, flip a coin to see if we do
-          if let CoinFlip::Heads = rand::thread_rng().gen::<CoinFlip>() {
+          if let CoinFlip::Heads = rng.gen::<CoinFlip>() {
             // nope, closing it out
-            close_run!(grid, run);
+            close_run!(grid, seams, run, rng);
           }
         }
       }
     }
     // close out any remaining run
-    close_run!(grid, run);
+    close_run!(grid, seams, run, rng);
     row_idx = row_idx + 1;
   }
   grid
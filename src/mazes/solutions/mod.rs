@@ -1,36 +1,93 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::grid::cell::GridCoords;
+
+// a route found between two cells, along with every cell explored looking
+// for it and how far that cell was from where the search reached it
+#[derive(Debug)]
+pub struct Route<C = GridCoords> {
+  // from the start to the goal, empty if the goal can't be reached
+  pub path: Vec<C>,
+  pub explored: HashMap<C, u32>,
+  pub max_distance: u32,
+}
+
+impl<C: Clone + Eq + Hash> Route<C> {
+  fn new(path: Vec<C>, explored: HashMap<C, u32>) -> Route<C> {
+    let max_distance = explored.values().cloned().max().unwrap_or(0);
+    Route {
+      path,
+      explored,
+      max_distance,
+    }
+  }
+}
+
+// follows `previous` from `coords` back to the cell the search started at
+fn walk_back<C: Clone + Eq + Hash>(previous: &HashMap<C, C>, coords: &C) -> Vec<C> {
+  let mut path = vec![coords.clone()];
+  let mut coords = coords;
+  while let Some(prev) = previous.get(coords) {
+    path.push(prev.clone());
+    coords = prev;
+  }
+  path
+}
+
 pub mod dijkstra {
+  use std::cmp::Reverse;
   use std::collections::*;
+  use std::fmt::Debug;
+  use std::hash::Hash;
 
   use crate::grid::cell::*;
-  use crate::grid::Grid;
+  use crate::grid::topology::Topology;
+
+  #[derive(Debug)]
+  pub struct LongestPath<C = GridCoords> {
+    pub start: C,
+    pub end: C,
+    // the summed cost of the path, which is only its number of steps when
+    // every step costs 1. `path` holds the cells themselves
+    pub length: u32,
+    pub path: Vec<C>,
+  }
 
   #[derive(Debug)]
-  pub struct Dijkstra {
-    pub distances: HashMap<GridCoords, u32>,
-    pub origin: GridCoords,
+  pub struct Dijkstra<C = GridCoords> {
+    pub distances: HashMap<C, u32>,
+    pub origin: C,
+    // the largest of `distances`, the cost of reaching the farthest cell
     pub max_distance: u32,
   }
 
-  impl Dijkstra {
-    pub fn new(grid: &Grid, origin: &GridCoords) -> Dijkstra {
+  impl<C: Clone + Eq + Hash + Ord + Debug> Dijkstra<C> {
+    // distances are the summed `Topology::cost` of each step away from the
+    // origin, the origin itself costs nothing
+    pub fn new<G: Topology<Coords = C>>(grid: &G, origin: &C) -> Dijkstra<C> {
       let mut distances = HashMap::new();
       let mut max_distance = 0;
-      let mut frontier: VecDeque<&GridCoords> = VecDeque::new();
-      let mut visited = HashSet::new();
-      frontier.push_back(origin);
+      // cheapest first, ties broken by the lowest coords
+      let mut frontier: BinaryHeap<Reverse<(u32, C)>> = BinaryHeap::new();
+      frontier.push(Reverse((0, origin.clone())));
       distances.insert(origin.clone(), 0);
-      while let Some(coords) = frontier.pop_front() {
-        visited.insert(coords);
-        let current_distance = distances.get(coords).unwrap();
-        let next_distance = current_distance + 1;
-        if next_distance > max_distance {
-          max_distance = next_distance;
+      while let Some(Reverse((distance, coords))) = frontier.pop() {
+        // already reached more cheaply since this was queued
+        if distance > distances[&coords] {
+          continue;
         }
-        let cell = grid.cell_at(coords).expect(format!("No cell found at coords, {:?}", coords).as_ref());
-        for to in grid.links(cell) {
-          if !visited.contains(to.coords()) {
-            distances.insert(to.coords().clone(), next_distance);
-            frontier.push_back(to.coords());
+        if distance > max_distance {
+          max_distance = distance;
+        }
+        for to in grid.linked_coords(&coords) {
+          let next_distance = distance.saturating_add(grid.cost(&coords, &to));
+          let shorter = match distances.get(&to) {
+            Some(known) => next_distance < *known,
+            None => true
+          };
+          if shorter {
+            distances.insert(to.clone(), next_distance);
+            frontier.push(Reverse((next_distance, to)));
           }
         }
       }
@@ -41,34 +98,240 @@ pub mod dijkstra {
       }
     }
 
-    pub fn shortest_path_to<'a>(&self, grid: &Grid, dest: &GridCoords) -> Vec<GridCoords> {
+    // walks back from `dest` to the origin, each step going to a linked cell
+    // the cheapest route passes through. empty if `dest` can't be reached
+    pub fn shortest_path_to<G: Topology<Coords = C>>(&self, grid: &G, dest: &C) -> Vec<C> {
       let mut path = Vec::new();
-      let mut next = Some(dest);
+      if !self.distances.contains_key(dest) {
+        return path;
+      }
+      let mut next = Some(dest.clone());
       let mut visited = HashSet::new();
       while let Some(coords) = next {
         path.push(coords.clone());
-        if &self.origin == coords {
+        if self.origin == coords {
           return path;
         }
+        let distance = self.distances[&coords];
+        next = grid.linked_coords(&coords).into_iter().filter(|linked| {
+          !visited.contains(linked) && match self.distances.get(linked) {
+            Some(linked_distance) => linked_distance.saturating_add(grid.cost(linked, &coords)) == distance,
+            None => false
+          }
+        }).min();
         visited.insert(coords);
-        next = None;
-        let cell = grid.cell_at(coords).unwrap();
-        let mut min_dist = &u32::max_value();
-        for linked_cell in grid.links(cell) {
-          if !visited.contains(linked_cell.coords()) {
-            let distance = self.distances.get(linked_cell.coords()).unwrap();
-            if distance <= min_dist {
-              min_dist = distance;
-              next = Some(linked_cell.coords());
+      }
+      path
+    }
+
+    // the reachable cell furthest from the origin, ties broken by the lowest coords
+    pub fn farthest(&self) -> (C, u32) {
+      let mut farthest = (self.origin.clone(), 0);
+      for (coords, distance) in &self.distances {
+        let (ref best, best_distance) = farthest;
+        if *distance > best_distance || (*distance == best_distance && coords < best) {
+          farthest = (coords.clone(), *distance);
+        }
+      }
+      farthest
+    }
+
+    // double sweep: the cell furthest from any cell is one end of the
+    // longest path, the cell furthest from that is the other end. "longest"
+    // goes by `Topology::cost`, so `length` is a cost rather than a count of
+    // steps on a weighted grid
+    pub fn longest_path<G: Topology<Coords = C>>(grid: &G) -> Option<LongestPath<C>> {
+      let any = grid.coords().into_iter().next()?;
+      let (start, _) = Dijkstra::new(grid, &any).farthest();
+      let distances = Dijkstra::new(grid, &start);
+      let (end, length) = distances.farthest();
+      let mut path = distances.shortest_path_to(grid, &end);
+      path.reverse();
+      Some(LongestPath {
+        start,
+        end,
+        length,
+        path,
+      })
+    }
+  }
+}
+
+pub mod astar {
+  use std::cmp::Reverse;
+  use std::collections::*;
+
+  use crate::grid::Grid;
+  use crate::grid::cell::*;
+  use crate::grid::topology::Topology;
+  use super::{Route, walk_back};
+
+  // estimates of the number of steps left to the goal, going the short way
+  // round on a wrapping grid. `solve` scales them by the cheapest step, so
+  // on a grid with a step that costs nothing they estimate nothing at all
+  #[derive(Debug, Clone, Copy, PartialEq)]
+  pub enum Heuristic {
+    Manhattan,
+    Euclidean,
+  }
+
+  impl Heuristic {
+    pub fn estimate(self, grid: &Grid, from: &GridCoords, to: &GridCoords) -> u32 {
+      let rows = apart(from.row_index, to.row_index, grid.height(), grid.wraps_north());
+      let cols = apart(from.col_index, to.col_index, grid.width(), grid.wraps_east());
+      let estimate = match self {
+        Heuristic::Manhattan => rows + cols,
+        Heuristic::Euclidean => (rows * rows + cols * cols).sqrt().floor(),
+      };
+      estimate.min(f64::from(u32::max_value())) as u32
+    }
+  }
+
+  // how many rows or columns lie between two indexes along an axis `len` long
+  fn apart(from: usize, to: usize, len: usize, wraps: bool) -> f64 {
+    let apart = if from > to { from - to } else { to - from };
+    if wraps {
+      apart.min(len - apart) as f64
+    } else {
+      apart as f64
+    }
+  }
+
+  // the cheapest route from `start` to `goal`, only exploring cells that
+  // look like they might be on it. square grids only, the estimates count
+  // rows and columns so they'd overshoot on hex or polar grids
+  pub fn solve(grid: &Grid, start: &GridCoords, goal: &GridCoords, heuristic: Heuristic) -> Route {
+    let mut distances = HashMap::new();
+    let mut previous = HashMap::new();
+    let mut explored = HashMap::new();
+    // lowest estimated total first, then whichever looks closest to the goal
+    let mut frontier: BinaryHeap<Reverse<(u32, u32, GridCoords)>> = BinaryHeap::new();
+    let min_cost = grid.min_cost();
+    let estimate = heuristic.estimate(grid, start, goal).saturating_mul(min_cost);
+    frontier.push(Reverse((estimate, estimate, *start)));
+    distances.insert(*start, 0);
+    while let Some(Reverse((_, _, coords))) = frontier.pop() {
+      if explored.contains_key(&coords) {
+        continue;
+      }
+      let distance = distances[&coords];
+      explored.insert(coords, distance);
+      if &coords == goal {
+        let mut path = walk_back(&previous, &coords);
+        path.reverse();
+        return Route::new(path, explored);
+      }
+      for to in grid.linked_coords(&coords) {
+        let next_distance = distance.saturating_add(grid.cost(&coords, &to));
+        let shorter = match distances.get(&to) {
+          Some(known) => next_distance < *known,
+          None => true
+        };
+        if shorter {
+          let estimate = heuristic.estimate(grid, &to, goal).saturating_mul(min_cost);
+          distances.insert(to, next_distance);
+          previous.insert(to, coords);
+          frontier.push(Reverse((next_distance.saturating_add(estimate), estimate, to)));
+        }
+      }
+    }
+    Route::new(Vec::new(), explored)
+  }
+}
+
+pub mod bidirectional {
+  use std::collections::*;
+  use std::hash::Hash;
+
+  use crate::grid::topology::Topology;
+  use super::{Route, walk_back};
+
+  // one end of the search: how far each cell reached is from that end, the
+  // cell it was reached from and the cells reached last
+  struct Side<C> {
+    distances: HashMap<C, u32>,
+    previous: HashMap<C, C>,
+    frontier: Vec<C>,
+  }
+
+  impl<C: Clone + Eq + Hash + Ord> Side<C> {
+    fn new(origin: &C) -> Side<C> {
+      let mut distances = HashMap::new();
+      distances.insert(origin.clone(), 0);
+      Side {
+        distances,
+        previous: HashMap::new(),
+        frontier: vec![origin.clone()],
+      }
+    }
+
+    // takes one step out from every cell in the frontier, returning the
+    // cheapest cell where the search met `other`, if it did
+    fn expand<G: Topology<Coords = C>>(&mut self, grid: &G, other: &Side<C>) -> Option<(u32, C)> {
+      let mut met: Option<(u32, C)> = None;
+      let mut next = Vec::new();
+      for coords in self.frontier.drain(..) {
+        let distance = self.distances[&coords] + 1;
+        for to in grid.linked_coords(&coords) {
+          if self.distances.contains_key(&to) {
+            continue;
+          }
+          self.distances.insert(to.clone(), distance);
+          self.previous.insert(to.clone(), coords.clone());
+          if let Some(other_distance) = other.distances.get(&to) {
+            let total = distance + other_distance;
+            let cheaper = match &met {
+              Some((best, best_coords)) => (total, &to) < (*best, best_coords),
+              None => true
+            };
+            if cheaper {
+              met = Some((total, to.clone()));
             }
           }
+          next.push(to);
         }
       }
-      path
+      self.frontier = next;
+      met
     }
+  }
 
-    pub fn longest_path() {
+  // the shortest route from `start` to `goal` counting every step the same,
+  // searching out from both ends at once until they meet in the middle
+  pub fn solve<G: Topology>(grid: &G, start: &G::Coords, goal: &G::Coords) -> Route<G::Coords> {
+    let mut forward = Side::new(start);
+    let mut backward = Side::new(goal);
+    let mut met = if start == goal {
+      Some(start.clone())
+    } else {
+      None
+    };
+    while met.is_none() && !forward.frontier.is_empty() && !backward.frontier.is_empty() {
+      // grow whichever end has less to look at
+      let reached = if forward.frontier.len() <= backward.frontier.len() {
+        forward.expand(grid, &backward)
+      } else {
+        backward.expand(grid, &forward)
+      };
+      met = reached.map(|(_, coords)| coords);
+    }
 
+    let path = match met {
+      Some(middle) => {
+        let mut path = walk_back(&forward.previous, &middle);
+        path.reverse();
+        path.extend(walk_back(&backward.previous, &middle).into_iter().skip(1));
+        path
+      }
+      None => Vec::new()
+    };
+    let mut explored = forward.distances;
+    for (coords, distance) in backward.distances {
+      let nearest = explored.entry(coords).or_insert(distance);
+      if distance < *nearest {
+        *nearest = distance;
+      }
     }
+    Route::new(path, explored)
   }
 }
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use crate::grid::topology::Topology;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::{assert_connected, RandomSet};
+
+pub fn apply_to<G: Topology>(grid: G) -> G {
+  apply_to_with_rng(grid, &mut rand::thread_rng())
+}
+
+// panics if some cells can't be reached from the rest
+pub fn apply_to_with_rng<G: Topology, R: Rng + ?Sized>(mut grid: G, rng: &mut R) -> G {
+  assert_connected(&grid, "Wilson's");
+  let mut unvisited = RandomSet::new(grid.coords());
+  // seed the maze with a single random cell
+  if let Some(first) = unvisited.choose(rng) {
+    unvisited.remove(&first);
+  }
+  carve(&mut grid, rng, &mut unvisited);
+  grid
+}
+
+// loop-erased random walks from random unvisited cells, each walk is added
+// to the maze once it reaches a visited cell. expects at least one cell to
+// have been visited already
+pub(super) fn carve<G: Topology, R: Rng + ?Sized>(grid: &mut G, rng: &mut R, unvisited: &mut RandomSet<G::Coords>) {
+  'walks: while let Some(start) = unvisited.choose(rng) {
+    let mut path = vec![start.clone()];
+    let mut path_index: HashMap<G::Coords, usize> = HashMap::new();
+    path_index.insert(start.clone(), 0);
+    let mut current = start;
+    while unvisited.contains(&current) {
+      let next = match grid.neighbor_coords(&current).choose(rng) {
+        Some(neighbor) => neighbor.clone(),
+        None => {
+          // a lone cell can never reach the maze
+          unvisited.remove(&current);
+          continue 'walks;
+        }
+      };
+      match path_index.get(&next) {
+        Some(&idx) => {
+          // walked into our own path, erase the loop
+          for erased in path.drain(idx + 1..) {
+            path_index.remove(&erased);
+          }
+        }
+        None => {
+          path_index.insert(next.clone(), path.len());
+          path.push(next.clone());
+        }
+      }
+      current = next;
+    }
+    // carve back from the maze. carving a step can rule out one carved
+    // later, e.g. a tunnel under a corridor the walk then turns into, so
+    // the walk stops there and the rest of it stays unvisited
+    for i in (1..path.len()).rev() {
+      if !grid.neighbor_coords(&path[i]).contains(&path[i - 1]) {
+        break;
+      }
+      grid.link_bidi(&path[i], &path[i - 1]);
+      unvisited.remove(&path[i - 1]);
+    }
+  }
+}
@@ -1,12 +1,27 @@
 extern crate grid_rs;
-use grid_rs::grid::img::{to_img, draw_distance_gradation};
+use grid_rs::grid::img::{to_img, draw_distance_gradation, draw_solution};
 use grid_rs::prelude::*;
+use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 
 fn make_grid() -> Grid {
   let grid = Grid::new(10, 10);
   grid
 }
 
+fn link_count<G: Topology>(grid: &G) -> usize {
+  grid.coords().iter().map(|coords| grid.linked_coords(coords).len()).sum::<usize>() / 2
+}
+
+// every cell reachable and no loops
+fn assert_perfect<G: Topology>(grid: &G) {
+  let coords = grid.coords();
+  let distances = solutions::dijkstra::Dijkstra::new(grid, &coords[0]);
+  assert_eq!(coords.len(), distances.distances.len());
+  assert_eq!(coords.len() - 1, link_count(grid));
+}
+
 #[test]
 fn graph_len() {
   let grid = make_grid();
@@ -117,6 +132,1454 @@ fn sidewinder_tree_maze() {
   println!("{}", grid);
 }
 
+#[test]
+fn seeded_binary_tree_is_reproducible() {
+  let first = binarytree::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(42));
+  let second = binarytree::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(42));
+  assert_eq!(format!("{}", first), format!("{}", second));
+}
+
+#[test]
+fn seeded_sidewinder_is_reproducible() {
+  let first = sidewinder::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(42));
+  let second = sidewinder::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(42));
+  assert_eq!(format!("{}", first), format!("{}", second));
+}
+
+#[test]
+fn seeded_rand_cell_is_reproducible() {
+  let grid = make_grid();
+  let first = *grid.rand_cell_with_rng(&mut StdRng::seed_from_u64(7)).coords();
+  let second = *grid.rand_cell_with_rng(&mut StdRng::seed_from_u64(7)).coords();
+  assert_eq!(first, second);
+}
+
+#[test]
+fn aldous_broder_maze() {
+  let grid = aldous_broder::apply_to(make_grid());
+  assert_perfect(&grid);
+}
+
+#[test]
+fn wilsons_maze() {
+  let grid = wilsons::apply_to(make_grid());
+  assert_perfect(&grid);
+}
+
+#[test]
+fn hybrid_maze() {
+  for switch_at in &[0.0, 0.5, 1.0] {
+    let grid = hybrid::apply_to(make_grid(), *switch_at);
+    assert_perfect(&grid);
+  }
+}
+
+#[test]
+fn recursive_backtracker_maze() {
+  let grid = recursive_backtracker::apply_to(make_grid());
+  assert_perfect(&grid);
+}
+
+#[test]
+fn recursive_backtracker_large_maze() {
+  let grid = recursive_backtracker::apply_to_with_rng(Grid::new(200, 200), &mut StdRng::seed_from_u64(1));
+  assert_eq!(200 * 200 - 1, link_count(&grid));
+}
+
+#[test]
+fn hunt_and_kill_maze() {
+  let start = GridCoords {
+    col_index: 3,
+    row_index: 7,
+  };
+  let grid = hunt_and_kill::apply_to_with_start(make_grid(), &start, &mut StdRng::seed_from_u64(5));
+  assert_perfect(&grid);
+  let grid = hunt_and_kill::apply_to(make_grid());
+  assert_perfect(&grid);
+}
+
+#[test]
+fn kruskals_maze() {
+  let grid = kruskals::apply_to(make_grid());
+  assert_perfect(&grid);
+}
+
+#[test]
+fn kruskals_maze_with_links() {
+  let a = GridCoords {
+    col_index: 4,
+    row_index: 4,
+  };
+  let b = GridCoords {
+    col_index: 5,
+    row_index: 4,
+  };
+  let grid = kruskals::apply_to_with_links(make_grid(), &[(a, b)], &mut StdRng::seed_from_u64(9));
+  assert_perfect(&grid);
+  let linked = grid.links(grid.cell_at(&a).unwrap());
+  assert!(linked.iter().any(|cell| cell.coords() == &b));
+}
+
+#[test]
+fn kruskals_maze_skips_bad_links() {
+  let at = |row_index, col_index| GridCoords { col_index, row_index };
+  // a loop round four cells, and two cells that aren't next to each other
+  let links = [
+    (at(0, 0), at(0, 1)),
+    (at(0, 1), at(1, 1)),
+    (at(1, 1), at(1, 0)),
+    (at(1, 0), at(0, 0)),
+    (at(5, 5), at(7, 7)),
+  ];
+  let grid = kruskals::apply_to_with_links(make_grid(), &links, &mut StdRng::seed_from_u64(5));
+  assert_perfect(&grid);
+  assert!(grid.is_linked(&at(0, 0), &at(0, 1)));
+  assert!(grid.is_linked(&at(1, 1), &at(1, 0)));
+  assert!(!grid.is_linked(&at(1, 0), &at(0, 0)));
+  assert!(!grid.is_linked(&at(5, 5), &at(7, 7)));
+}
+
+#[test]
+fn union_find() {
+  let mut sets = kruskals::UnionFind::new();
+  let a = GridCoords {
+    col_index: 0,
+    row_index: 0,
+  };
+  let b = GridCoords {
+    col_index: 1,
+    row_index: 0,
+  };
+  let c = GridCoords {
+    col_index: 2,
+    row_index: 0,
+  };
+  assert!(sets.union(&a, &b));
+  assert!(!sets.union(&b, &a));
+  assert_ne!(sets.find(&a), sets.find(&c));
+  assert!(sets.union(&c, &b));
+  assert_eq!(sets.find(&a), sets.find(&c));
+}
+
+#[test]
+fn prims_mazes() {
+  let grid = prims::apply_simplified_to(make_grid());
+  assert_perfect(&grid);
+  let grid = prims::apply_true_to(make_grid());
+  assert_perfect(&grid);
+}
+
+#[test]
+fn growing_tree_mazes() {
+  assert_perfect(&growing_tree::apply_to(make_grid(), growing_tree::Newest));
+  assert_perfect(&growing_tree::apply_to(make_grid(), growing_tree::Oldest));
+  assert_perfect(&growing_tree::apply_to(make_grid(), growing_tree::Random));
+  assert_perfect(&growing_tree::apply_to(make_grid(), growing_tree::Middle));
+  let mix = growing_tree::Mix::new()
+    .with(75, growing_tree::Newest)
+    .with(25, growing_tree::Random);
+  assert_perfect(&growing_tree::apply_to(make_grid(), mix));
+}
+
+#[test]
+fn seeded_growing_tree_is_reproducible() {
+  let mix = || growing_tree::Mix::new().with(1, growing_tree::Newest).with(1, growing_tree::Random);
+  let first = growing_tree::apply_to_with_rng(make_grid(), mix(), &mut StdRng::seed_from_u64(42));
+  let second = growing_tree::apply_to_with_rng(make_grid(), mix(), &mut StdRng::seed_from_u64(42));
+  assert_eq!(format!("{}", first), format!("{}", second));
+}
+
+#[test]
+fn ellers_maze() {
+  let grid = ellers::apply_to(make_grid());
+  assert_perfect(&grid);
+  let grid = ellers::apply_to(Grid::new(1, 10));
+  assert_perfect(&grid);
+}
+
+#[test]
+fn ellers_stream() {
+  let mut rows = ellers::Rows::new(8, StdRng::seed_from_u64(11));
+  for (idx, row) in rows.by_ref().take(1000).enumerate() {
+    assert_eq!(idx, row.row_index);
+    assert_eq!(7, row.east.len());
+    assert!(row.north.iter().any(|north| *north));
+  }
+  let last = rows.last_row();
+  assert_eq!(1000, last.row_index);
+  assert!(last.north.iter().all(|north| !*north));
+  assert!(rows.next().is_none());
+}
+
+#[test]
+fn link_all_and_unlink() {
+  let mut grid = make_grid();
+  grid.link_all();
+  // 10 rows of 9 east-west links plus 10 columns of 9 north-south links
+  assert_eq!(180, link_count(&grid));
+  let a = GridCoords {
+    col_index: 0,
+    row_index: 0,
+  };
+  let b = GridCoords {
+    col_index: 1,
+    row_index: 0,
+  };
+  grid.unlink_bidi(&a, &b);
+  assert_eq!(179, link_count(&grid));
+  assert_eq!(1, grid.links(grid.cell_at(&a).unwrap()).len());
+  // pairs that are already unlinked are skipped
+  let c = GridCoords {
+    col_index: 0,
+    row_index: 1,
+  };
+  grid.unlink_bidi_all(&[(a, b), (a, c), (c, a)]);
+  assert_eq!(178, link_count(&grid));
+  assert!(grid.links(grid.cell_at(&a).unwrap()).is_empty());
+  assert!(!grid.is_linked(&c, &a));
+}
+
+#[test]
+fn link_mutation() {
+  let mut grid = make_grid();
+  let a = GridCoords {
+    col_index: 3,
+    row_index: 3,
+  };
+  let b = GridCoords {
+    col_index: 3,
+    row_index: 4,
+  };
+  grid.link(&a, &b);
+  assert!(grid.is_linked(&a, &b));
+  assert!(!grid.is_linked(&b, &a));
+  grid.unlink(&a, &b);
+  assert!(!grid.is_linked(&a, &b));
+  grid.link_bidi(&a, &b);
+  assert!(grid.is_linked(&a, &b) && grid.is_linked(&b, &a));
+  // linking again, or again after unlinking, never lists a link twice
+  grid.link_bidi(&a, &b);
+  grid.unlink_bidi(&a, &b);
+  grid.link_bidi(&a, &b);
+  grid.link(&a, &b);
+  assert_eq!(1, grid.links(grid.cell_at(&a).unwrap()).len());
+  assert_eq!(1, link_count(&grid));
+  grid.link_all();
+  grid.clear_links();
+  assert_eq!(0, link_count(&grid));
+  assert!(!grid.is_linked(&a, &b));
+}
+
+#[test]
+fn recursive_division_maze() {
+  let grid = recursive_division::apply_to(make_grid());
+  assert_perfect(&grid);
+}
+
+#[test]
+fn recursive_division_rooms() {
+  let grid = recursive_division::apply_to_with_rooms(make_grid(), 4, &mut StdRng::seed_from_u64(2));
+  let origin = GridCoords {
+    col_index: 0,
+    row_index: 0,
+  };
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &origin);
+  assert_eq!(100, distances.distances.len());
+  // open rooms mean loops, so more links than a perfect maze
+  assert!(link_count(&grid) > 99);
+}
+
+fn make_masked_grid() -> Grid {
+  let mask = Mask::from_text("\
+.........X
+..........
+...XXXX...
+...XXXX...
+..........
+X.........
+..........
+.....X....
+..........
+.........X
+");
+  Grid::from_mask(&mask)
+}
+
+#[test]
+fn mask_from_text() {
+  let mask = Mask::from_text("X..\n...\n..X\n");
+  assert_eq!(3, mask.height());
+  assert_eq!(3, mask.width());
+  assert_eq!(7, mask.count());
+  // the first line of text is the top row
+  assert!(!mask.is_on(&GridCoords {
+    col_index: 0,
+    row_index: 2,
+  }));
+  assert!(!mask.is_on(&GridCoords {
+    col_index: 2,
+    row_index: 0,
+  }));
+  let grid = Grid::from_mask(&mask);
+  assert_eq!(7, grid.cells().len());
+  assert_eq!(7, grid.coords().len());
+  assert!(grid.rows().iter().all(|row| row.len() < 3 || row[1].col_index == 1));
+}
+
+#[test]
+fn mask_from_png() {
+  let white = image::Rgb { data: [255, 255, 255] };
+  let black = image::Rgb { data: [0, 0, 0] };
+  let mut img: image::RgbImage = image::ImageBuffer::from_pixel(4, 3, white);
+  img.put_pixel(0, 0, black);
+  img.put_pixel(2, 1, black);
+  img.save("test-output/mask.png").unwrap();
+  let mask = Mask::from_png("test-output/mask.png").unwrap();
+  assert_eq!(3, mask.height());
+  assert_eq!(4, mask.width());
+  assert_eq!(10, mask.count());
+  assert!(!mask.is_on(&GridCoords {
+    col_index: 0,
+    row_index: 2,
+  }));
+  assert!(!mask.is_on(&GridCoords {
+    col_index: 2,
+    row_index: 1,
+  }));
+}
+
+#[test]
+fn masked_rand_cell() {
+  let grid = make_masked_grid();
+  let mut rng = StdRng::seed_from_u64(4);
+  for _ in 0..500 {
+    let cell = grid.rand_cell_with_rng(&mut rng);
+    assert!(grid.cell_at(cell.coords()).is_some());
+  }
+}
+
+#[test]
+fn masked_mazes() {
+  assert_perfect(&aldous_broder::apply_to(make_masked_grid()));
+  assert_perfect(&wilsons::apply_to(make_masked_grid()));
+  assert_perfect(&hybrid::apply_to(make_masked_grid(), 0.5));
+  assert_perfect(&recursive_backtracker::apply_to(make_masked_grid()));
+  assert_perfect(&hunt_and_kill::apply_to(make_masked_grid()));
+  assert_perfect(&kruskals::apply_to(make_masked_grid()));
+  assert_perfect(&prims::apply_simplified_to(make_masked_grid()));
+  assert_perfect(&prims::apply_true_to(make_masked_grid()));
+  assert_perfect(&growing_tree::apply_to(make_masked_grid(), growing_tree::Random));
+}
+
+#[test]
+fn masked_walks_need_one_piece() {
+  // the masked out column splits the grid in two
+  let split = || Grid::from_mask(&Mask::from_text("..X..\n..X..\n..X..\n"));
+  let walks: Vec<Box<dyn Fn() -> Grid + std::panic::UnwindSafe>> = vec![
+    Box::new(|| aldous_broder::apply_to(split())),
+    Box::new(|| wilsons::apply_to(split())),
+    Box::new(|| hybrid::apply_to(split(), 0.5)),
+  ];
+  for walk in walks {
+    let result = std::panic::catch_unwind(walk);
+    let message = result.err().and_then(|err| err.downcast_ref::<String>().cloned()).unwrap();
+    assert!(message.ends_with("6 of 12 cells are cut off"), "{}", message);
+  }
+  // Kruskal's carves each piece on its own
+  let grid = kruskals::apply_to(split());
+  assert_eq!(10, link_count(&grid));
+}
+
+#[test]
+fn masked_rows_rejected() {
+  assert!(make_masked_grid().is_masked());
+  assert!(!make_grid().is_masked());
+  let binary_tree = std::panic::catch_unwind(|| binarytree::apply_to(make_masked_grid()));
+  assert!(binary_tree.is_err());
+  let sidewinder = std::panic::catch_unwind(|| sidewinder::apply_to(make_masked_grid()));
+  assert!(sidewinder.is_err());
+}
+
+#[test]
+#[should_panic(expected = "the grid is masked")]
+fn masked_ellers_rejected() {
+  ellers::apply_to(make_masked_grid());
+}
+
+#[test]
+#[should_panic(expected = "the grid is masked")]
+fn masked_recursive_division_rejected() {
+  recursive_division::apply_to(make_masked_grid());
+}
+
+#[test]
+fn masked_display() {
+  let grid = Grid::from_mask(&Mask::from_text("X.\n..\n"));
+  let expected = "\
++   +---+
+    |   |
++---+---+
+|   |   |
++---+---+
+";
+  assert_eq!(expected, format!("{}", grid));
+}
+
+#[test]
+fn masked_to_image() {
+  let grid = recursive_backtracker::apply_to(make_masked_grid());
+  let grid_image = grid.to_img("test-output/masked.png", 10);
+  assert_eq!((110, 110), grid_image.canvas.dimensions());
+  let white = image::Rgb { data: [255, 255, 255] };
+  let black = image::Rgb { data: [0, 0, 0] };
+  // inside the masked out block in rows 6 and 7, columns 3 to 6
+  assert_eq!(&white, grid_image.canvas.get_pixel(50, 39));
+  // no wall between two masked out cells
+  assert_eq!(&white, grid_image.canvas.get_pixel(50, 34));
+  // the wall along the bottom of the block
+  assert_eq!(&black, grid_image.canvas.get_pixel(50, 44));
+}
+
+#[test]
+fn polar_grid_rings() {
+  let grid = PolarGrid::new(8);
+  assert_eq!(8, grid.height());
+  assert_eq!(1, grid.row_length(0));
+  assert_eq!(6, grid.row_length(1));
+  for row_index in 1..grid.height() {
+    // every ring evenly subdivides the ring inside it
+    assert_eq!(0, grid.row_length(row_index) % grid.row_length(row_index - 1));
+  }
+  assert_eq!(grid.cells().len(), grid.coords().len());
+}
+
+#[test]
+fn polar_grid_neighbors() {
+  let grid = PolarGrid::new(8);
+  for coords in grid.coords() {
+    for neighbor in grid.neighbor_coords(&coords) {
+      assert!(grid.neighbor_coords(&neighbor).contains(&coords));
+    }
+  }
+  let center = grid.cell_at(&GridCoords {
+    col_index: 0,
+    row_index: 0,
+  }).unwrap();
+  assert!(grid.cw(center).is_none());
+  assert!(grid.inward(center).is_none());
+  assert_eq!(6, grid.outward(center).len());
+}
+
+#[test]
+fn polar_mazes() {
+  let mazes = vec![
+    recursive_backtracker::apply_to(PolarGrid::new(10)),
+    aldous_broder::apply_to(PolarGrid::new(10)),
+    wilsons::apply_to(PolarGrid::new(10)),
+    kruskals::apply_to(PolarGrid::new(10)),
+    growing_tree::apply_to(PolarGrid::new(10), growing_tree::Middle),
+  ];
+  for grid in mazes {
+    assert_perfect(&grid);
+  }
+}
+
+#[test]
+fn polar_to_image_test() {
+  let grid = recursive_backtracker::apply_to(PolarGrid::new(12));
+  grid.to_img("test-output/polar.png", 12);
+}
+
+#[test]
+fn hex_grid_neighbors() {
+  let grid = HexGrid::new(6, 6);
+  for coords in grid.coords() {
+    for neighbor in grid.neighbor_coords(&coords) {
+      assert!(grid.neighbor_coords(&neighbor).contains(&coords));
+    }
+  }
+  let inner = grid.cell_at(&GridCoords {
+    col_index: 2,
+    row_index: 2,
+  }).unwrap();
+  assert_eq!(6, grid.neighbors(inner).to_vec().len());
+  let corner = grid.cell_at(&GridCoords {
+    col_index: 0,
+    row_index: 0,
+  }).unwrap();
+  assert_eq!(2, grid.neighbors(corner).to_vec().len());
+}
+
+#[test]
+fn hex_grid_display() {
+  let grid = HexGrid::new(2, 2);
+  let expected = "    __
+ __/  \\
+/  \\__/
+\\__/  \\
+/  \\__/
+\\__/
+";
+  assert_eq!(expected, format!("{}", grid));
+}
+
+#[test]
+fn hex_mazes() {
+  let mazes = vec![
+    recursive_backtracker::apply_to(HexGrid::new(8, 12)),
+    wilsons::apply_to(HexGrid::new(8, 12)),
+    prims::apply_true_to(HexGrid::new(8, 12)),
+  ];
+  for grid in mazes {
+    assert_perfect(&grid);
+  }
+}
+
+#[test]
+fn hex_to_image_test() {
+  let grid = recursive_backtracker::apply_to(HexGrid::new(10, 14));
+  println!("{}", grid);
+  grid.to_img("test-output/hex.png", 10);
+}
+
+#[test]
+fn triangle_grid_neighbors() {
+  let grid = TriangleGrid::new(6, 10);
+  for coords in grid.coords() {
+    let neighbors = grid.neighbor_coords(&coords);
+    assert!(neighbors.len() <= 3);
+    for neighbor in neighbors {
+      assert!(grid.neighbor_coords(&neighbor).contains(&coords));
+    }
+  }
+  let upright = grid.cell_at(&GridCoords {
+    col_index: 2,
+    row_index: 2,
+  }).unwrap();
+  assert!(grid.is_upright(upright));
+  assert!(grid.north(upright).is_none());
+  assert!(grid.south(upright).is_some());
+  let inverted = grid.cell_at(&GridCoords {
+    col_index: 3,
+    row_index: 2,
+  }).unwrap();
+  assert!(!grid.is_upright(inverted));
+  assert!(grid.north(inverted).is_some());
+  assert!(grid.south(inverted).is_none());
+}
+
+#[test]
+fn triangle_mazes() {
+  let mazes = vec![
+    recursive_backtracker::apply_to(TriangleGrid::new(8, 12)),
+    aldous_broder::apply_to(TriangleGrid::new(8, 12)),
+    kruskals::apply_to(TriangleGrid::new(8, 12)),
+  ];
+  for grid in mazes {
+    assert_perfect(&grid);
+  }
+}
+
+#[test]
+fn triangle_to_image_test() {
+  let grid = recursive_backtracker::apply_to(TriangleGrid::new(10, 20));
+  grid.to_img("test-output/triangle.png", 16);
+}
+
+#[test]
+fn weave_tunnel() {
+  let mut grid = WeaveGrid::new(3, 3);
+  let at = |row_index, col_index| GridCoords {
+    col_index,
+    row_index
+  };
+  // a north-south corridor down the middle column
+  grid.link_bidi(&WeaveCoords::Over(at(0, 1)), &WeaveCoords::Over(at(1, 1)));
+  grid.link_bidi(&WeaveCoords::Over(at(1, 1)), &WeaveCoords::Over(at(2, 1)));
+  let west = WeaveCoords::Over(at(1, 0));
+  let east = WeaveCoords::Over(at(1, 2));
+  assert!(grid.neighbor_coords(&west).contains(&east));
+  assert!(!grid.neighbor_coords(&WeaveCoords::Over(at(0, 0))).contains(&WeaveCoords::Over(at(0, 2))));
+
+  grid.link_bidi(&west, &east);
+  let under = WeaveCoords::Under(at(1, 1));
+  assert_eq!(Some(Passage::EastWest), grid.under_at(&at(1, 1)));
+  assert!(grid.coords().contains(&under));
+  assert_eq!(vec![under], grid.linked_coords(&west));
+  assert_eq!(2, grid.linked_coords(&under).len());
+  // the corridor above can't turn into the tunnel
+  assert!(!grid.neighbor_coords(&WeaveCoords::Over(at(1, 1))).contains(&west));
+
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &west);
+  assert_eq!(Some(&2), distances.distances.get(&east));
+  assert_eq!(vec![east, under, west], distances.shortest_path_to(&grid, &east));
+
+  grid.unlink_bidi(&west, &east);
+  assert_eq!(None, grid.under_at(&at(1, 1)));
+  assert!(grid.linked_coords(&west).is_empty());
+}
+
+#[test]
+fn weave_tunnel_refused() {
+  let mut grid = WeaveGrid::new(3, 3);
+  let at = |row_index, col_index| WeaveCoords::Over(GridCoords {
+    col_index,
+    row_index
+  });
+  // no corridor to cross yet
+  assert!(!grid.link_bidi(&at(1, 0), &at(1, 2)));
+  assert_eq!(None, grid.under_at(&GridCoords { col_index: 1, row_index: 1 }));
+  assert!(grid.linked_coords(&at(1, 0)).is_empty());
+
+  grid.link_bidi(&at(0, 1), &at(1, 1));
+  grid.link_bidi(&at(1, 1), &at(2, 1));
+  // a T-junction isn't a straight corridor
+  grid.link_bidi(&at(1, 1), &at(1, 2));
+  assert!(!grid.link_bidi(&at(1, 0), &at(1, 2)));
+  grid.unlink_bidi(&at(1, 1), &at(1, 2));
+
+  assert!(grid.link_bidi(&at(1, 0), &at(1, 2)));
+  // the tunnel that's already there stays put
+  assert!(!grid.link_bidi(&at(0, 1), &at(2, 1)));
+  assert_eq!(Some(Passage::EastWest), grid.under_at(&GridCoords { col_index: 1, row_index: 1 }));
+  assert_eq!(1, grid.linked_coords(&at(0, 1)).len());
+  // and the corridor above it can't turn into it
+  assert!(!grid.link_bidi(&at(1, 1), &at(1, 2)));
+  assert!(!grid.neighbor_coords(&at(1, 2)).contains(&at(1, 1)));
+}
+
+#[test]
+fn weave_mazes() {
+  let mazes = vec![
+    recursive_backtracker::apply_to_with_rng(WeaveGrid::new(12, 12), &mut StdRng::seed_from_u64(7)),
+    hunt_and_kill::apply_to(WeaveGrid::new(12, 12)),
+    aldous_broder::apply_to(WeaveGrid::new(12, 12)),
+    wilsons::apply_to(WeaveGrid::new(12, 12)),
+    prims::apply_simplified_to(WeaveGrid::new(12, 12)),
+    growing_tree::apply_to(WeaveGrid::new(12, 12), growing_tree::Newest),
+  ];
+  // the long corridors of the backtracker leave room for tunnels
+  assert!(mazes[0].coords().iter().any(|coords| match coords {
+    WeaveCoords::Under(_) => true,
+    WeaveCoords::Over(_) => false
+  }));
+  for grid in mazes {
+    assert_perfect(&grid);
+  }
+}
+
+#[test]
+fn weave_tunnels_stay_under_corridors() {
+  let rng = &mut StdRng::seed_from_u64(21);
+  let mazes = vec![
+    aldous_broder::apply_to_with_rng(WeaveGrid::new(10, 10), rng),
+    wilsons::apply_to_with_rng(WeaveGrid::new(10, 10), rng),
+    hybrid::apply_to_with_rng(WeaveGrid::new(10, 10), 0.5, rng),
+    recursive_backtracker::apply_to_with_rng(WeaveGrid::new(10, 10), rng),
+    hunt_and_kill::apply_to_with_rng(WeaveGrid::new(10, 10), rng),
+    kruskals::apply_to_with_rng(WeaveGrid::new(10, 10), rng),
+    prims::apply_simplified_to_with_rng(WeaveGrid::new(10, 10), rng),
+    prims::apply_true_to_with_rng(WeaveGrid::new(10, 10), rng),
+    growing_tree::apply_to_with_rng(WeaveGrid::new(10, 10), growing_tree::Newest, rng),
+    growing_tree::apply_to_with_rng(WeaveGrid::new(10, 10), growing_tree::Random, rng),
+  ];
+  for grid in mazes {
+    assert_perfect(&grid);
+    for coords in grid.coords() {
+      for neighbor in grid.neighbor_coords(&coords) {
+        assert!(grid.neighbor_coords(&neighbor).contains(&coords), "{:?} {:?}", coords, neighbor);
+      }
+      let middle = match coords {
+        WeaveCoords::Under(middle) => middle,
+        WeaveCoords::Over(_) => continue
+      };
+      let surface = grid.grid();
+      let cell = surface.cell_at(&middle).unwrap();
+      let (corridor, sides) = match grid.under_at(&middle) {
+        Some(Passage::EastWest) => ([surface.north(cell), surface.south(cell)], [surface.east(cell), surface.west(cell)]),
+        Some(Passage::NorthSouth) => ([surface.east(cell), surface.west(cell)], [surface.north(cell), surface.south(cell)]),
+        None => unreachable!()
+      };
+      for next in corridor.iter() {
+        assert!(surface.is_linked(&middle, next.unwrap().coords()));
+      }
+      for next in sides.iter() {
+        assert!(!surface.is_linked(&middle, next.unwrap().coords()));
+      }
+    }
+  }
+}
+
+#[test]
+fn weave_to_image_test() {
+  let grid = recursive_backtracker::apply_to(WeaveGrid::new(15, 15));
+  grid.to_img("test-output/weave.png", 20, 0.2);
+}
+
+#[test]
+fn grid3d_neighbors() {
+  let grid = Grid3D::new(3, 4, 5);
+  assert_eq!(60, grid.cells().len());
+  let corner = grid.cell_at(&Coords3D {
+    level: 0,
+    row_index: 0,
+    col_index: 0
+  }).unwrap();
+  let neighbors = grid.neighbors(corner);
+  assert_eq!(3, neighbors.to_vec().len());
+  assert_eq!(1, neighbors.up.unwrap().coords().level);
+  assert!(neighbors.down.is_none());
+  let middle = grid.cell_at(&Coords3D {
+    level: 1,
+    row_index: 1,
+    col_index: 1
+  }).unwrap();
+  assert_eq!(6, grid.neighbors(middle).to_vec().len());
+}
+
+#[test]
+fn grid3d_mazes() {
+  let mazes = vec![
+    recursive_backtracker::apply_to(Grid3D::new(3, 5, 5)),
+    wilsons::apply_to(Grid3D::new(3, 5, 5)),
+    kruskals::apply_to(Grid3D::new(3, 5, 5)),
+    growing_tree::apply_to(Grid3D::new(3, 5, 5), growing_tree::Random),
+  ];
+  for grid in mazes {
+    assert_perfect(&grid);
+  }
+}
+
+#[test]
+fn grid3d_stairs() {
+  let mut grid = Grid3D::new(2, 2, 1);
+  let at = |level, row_index| Coords3D {
+    level,
+    row_index,
+    col_index: 0
+  };
+  grid.link_bidi(&at(0, 0), &at(0, 1));
+  grid.link_bidi(&at(0, 1), &at(1, 1));
+  grid.link_bidi(&at(1, 1), &at(1, 0));
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &at(0, 0));
+  assert_eq!(Some(&3), distances.distances.get(&at(1, 0)));
+  let path = distances.shortest_path_to(&grid, &at(1, 0));
+  assert_eq!(1, Grid3D::stair_moves(&path));
+}
+
+#[test]
+fn grid3d_fewest_stairs() {
+  let mut grid = Grid3D::new(2, 3, 3);
+  let at = |level, row_index, col_index| Coords3D {
+    level,
+    row_index,
+    col_index
+  };
+  // four steps over the top with two flights of stairs, or six steps around
+  // the bottom level without any
+  grid.link_bidi(&at(0, 0, 0), &at(1, 0, 0));
+  grid.link_bidi(&at(1, 0, 0), &at(1, 0, 1));
+  grid.link_bidi(&at(1, 0, 1), &at(1, 0, 2));
+  grid.link_bidi(&at(1, 0, 2), &at(0, 0, 2));
+  grid.link_bidi(&at(0, 0, 0), &at(0, 1, 0));
+  grid.link_bidi(&at(0, 1, 0), &at(0, 2, 0));
+  grid.link_bidi(&at(0, 2, 0), &at(0, 2, 1));
+  grid.link_bidi(&at(0, 2, 1), &at(0, 2, 2));
+  grid.link_bidi(&at(0, 2, 2), &at(0, 1, 2));
+  grid.link_bidi(&at(0, 1, 2), &at(0, 0, 2));
+  let goal = at(0, 0, 2);
+
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &at(0, 0, 0));
+  assert_eq!(Some(&4), distances.distances.get(&goal));
+  let path = distances.shortest_path_to(&grid, &goal);
+  assert_eq!(5, path.len());
+  assert_eq!(2, Grid3D::stair_moves(&path));
+
+  grid.set_stair_cost(3);
+  assert_eq!(3, grid.stair_cost());
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &at(0, 0, 0));
+  assert_eq!(Some(&6), distances.distances.get(&goal));
+  let path = distances.shortest_path_to(&grid, &goal);
+  assert_eq!(7, path.len());
+  assert_eq!(0, Grid3D::stair_moves(&path));
+}
+
+#[test]
+fn grid3d_to_image_test() {
+  let grid = recursive_backtracker::apply_to(Grid3D::new(3, 8, 8));
+  grid.to_img("test-output/grid3d.png", 20);
+}
+
+#[test]
+fn wrapping_neighbors() {
+  let at = |row_index, col_index| GridCoords {
+    col_index,
+    row_index
+  };
+  let cylinder = Grid::with_wrap(4, 5, Wrap::Cylinder);
+  let corner = cylinder.cell_at(&at(3, 4)).unwrap();
+  assert_eq!(&at(3, 0), cylinder.east(corner).unwrap().coords());
+  assert!(cylinder.north(corner).is_none());
+  assert_eq!(&at(0, 4), cylinder.west(cylinder.cell_at(&at(0, 0)).unwrap()).unwrap().coords());
+
+  let torus = Grid::with_wrap(4, 5, Wrap::Torus);
+  let corner = torus.cell_at(&at(3, 4)).unwrap();
+  assert_eq!(&at(0, 4), torus.north(corner).unwrap().coords());
+  assert_eq!(&at(3, 0), torus.east(corner).unwrap().coords());
+  assert_eq!(&at(3, 0), torus.south(torus.cell_at(&at(0, 0)).unwrap()).unwrap().coords());
+  assert_eq!(4, torus.neighbors(corner).to_vec().len());
+
+  // too narrow to wrap, east and west would be the same cell
+  let narrow = Grid::with_wrap(2, 2, Wrap::Torus);
+  assert!(narrow.east(narrow.cell_at(&at(0, 1)).unwrap()).is_none());
+}
+
+#[test]
+fn wrapping_mazes() {
+  for wrap in [Wrap::Cylinder, Wrap::Torus].iter().cloned() {
+    let rng = &mut StdRng::seed_from_u64(3);
+    let mazes = vec![
+      binarytree::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      sidewinder::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      ellers::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      recursive_division::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      aldous_broder::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      wilsons::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      recursive_backtracker::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      hunt_and_kill::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      kruskals::apply_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+      prims::apply_true_to_with_rng(Grid::with_wrap(8, 8, wrap), rng),
+    ];
+    let mut crossings = 0;
+    for grid in mazes {
+      assert_perfect(&grid);
+      crossings += (0..8).filter(|&row_index| grid.is_linked(&GridCoords {
+        col_index: 7,
+        row_index
+      }, &GridCoords {
+        col_index: 0,
+        row_index
+      })).count();
+    }
+    assert!(crossings > 0);
+  }
+}
+
+#[test]
+fn wrapping_display() {
+  let mut grid = Grid::with_wrap(2, 3, Wrap::Cylinder);
+  grid.link_all();
+  // the walls at the east and west edges are gone, the cells there are linked
+  let expected = "+---+---+---+\n             \n+   +   +   +\n             \n+---+---+---+\n";
+  assert_eq!(expected, format!("{}", grid));
+}
+
+#[test]
+fn wrapping_to_image_test() {
+  let grid = recursive_backtracker::apply_to(Grid::with_wrap(10, 10, Wrap::Torus));
+  grid.to_img("test-output/torus.png", 20);
+}
+
+#[test]
+fn dead_ends() {
+  let mut grid = Grid::new(1, 3);
+  let at = |col_index| GridCoords {
+    col_index,
+    row_index: 0
+  };
+  grid.link_bidi(&at(0), &at(1));
+  grid.link_bidi(&at(1), &at(2));
+  assert_eq!(vec![at(0), at(2)], grid.dead_ends());
+}
+
+#[test]
+fn braid_maze() {
+  let rng = &mut StdRng::seed_from_u64(11);
+  let mut grid = recursive_backtracker::apply_to_with_rng(make_grid(), rng);
+  let before = grid.dead_ends().len();
+  let links = link_count(&grid);
+  assert!(before > 0);
+
+  grid.braid_with_rng(0.0, rng);
+  assert_eq!(before, grid.dead_ends().len());
+
+  grid.braid_with_rng(0.5, rng);
+  let after = grid.dead_ends().len();
+  assert!(after < before);
+  assert!(link_count(&grid) > links);
+
+  // every dead end that's left gets linked, leaving none
+  grid.braid_with_rng(1.0, rng);
+  assert!(grid.dead_ends().is_empty());
+}
+
+#[test]
+fn weighted_dijkstra() {
+  let at = |row_index, col_index| GridCoords {
+    col_index,
+    row_index
+  };
+  let mut grid = Grid::new(2, 2);
+  grid.link_all();
+  assert_eq!(1, grid.weight(&at(0, 1)));
+
+  // mud in the bottom right sends the path round the top left
+  grid.set_weight(&at(0, 1), 5);
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &at(0, 0));
+  assert_eq!(Some(&2), distances.distances.get(&at(1, 1)));
+  assert_eq!(vec![at(1, 1), at(1, 0), at(0, 0)], distances.shortest_path_to(&grid, &at(1, 1)));
+  assert_eq!(5, distances.max_distance);
+
+  // until the way round the top left costs even more
+  grid.set_link_weight(&at(1, 0), &at(0, 0), 7);
+  assert_eq!(Some(7), grid.link_weight(&at(0, 0), &at(1, 0)));
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &at(0, 0));
+  assert_eq!(Some(&6), distances.distances.get(&at(1, 1)));
+  assert_eq!(vec![at(1, 1), at(0, 1), at(0, 0)], distances.shortest_path_to(&grid, &at(1, 1)));
+  assert_eq!(7, distances.max_distance);
+}
+
+#[test]
+fn weighted_braided_maze() {
+  let rng = &mut StdRng::seed_from_u64(19);
+  let mut grid = recursive_backtracker::apply_to_with_rng(make_grid(), rng);
+  grid.braid_with_rng(0.5, rng);
+  for coords in grid.coords() {
+    grid.set_weight(&coords, rng.gen_range(1, 10));
+  }
+  let origin = GridCoords {
+    col_index: 0,
+    row_index: 0
+  };
+  let weighted = solutions::dijkstra::Dijkstra::new(&grid, &origin);
+  let (farthest, distance) = weighted.farthest();
+  let path = weighted.shortest_path_to(&grid, &farthest);
+  assert_eq!(Some(&origin), path.last());
+  // the path costs exactly the distance found
+  let cost: u32 = path.windows(2).map(|step| grid.weight(&step[0])).sum();
+  assert_eq!(distance, cost);
+  assert_eq!(distance, weighted.max_distance);
+}
+
+// a route that only ever steps between linked cells
+fn assert_route(grid: &Grid, route: &solutions::Route, start: &GridCoords, goal: &GridCoords) {
+  assert_eq!(Some(start), route.path.first());
+  assert_eq!(Some(goal), route.path.last());
+  assert!(route.path.windows(2).all(|step| grid.is_linked(&step[0], &step[1])));
+  assert!(route.path.iter().all(|coords| route.explored.contains_key(coords)));
+}
+
+#[test]
+fn astar_matches_dijkstra() {
+  let rng = &mut StdRng::seed_from_u64(20);
+  let mut grid = recursive_backtracker::apply_to_with_rng(Grid::new(20, 20), rng);
+  grid.braid_with_rng(0.5, rng);
+  for coords in grid.coords() {
+    grid.set_weight(&coords, rng.gen_range(1, 5));
+  }
+  let start = GridCoords {
+    col_index: 0,
+    row_index: 0
+  };
+  let goal = GridCoords {
+    col_index: 19,
+    row_index: 19
+  };
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &start);
+  for heuristic in [solutions::astar::Heuristic::Manhattan, solutions::astar::Heuristic::Euclidean].iter() {
+    let route = solutions::astar::solve(&grid, &start, &goal, *heuristic);
+    assert_route(&grid, &route, &start, &goal);
+    assert_eq!(distances.distances.get(&goal), route.explored.get(&goal));
+  }
+}
+
+#[test]
+fn astar_matches_dijkstra_wrapped_and_weighted() {
+  let rng = &mut StdRng::seed_from_u64(22);
+  for round in 0..30 {
+    for wrap in [Wrap::Flat, Wrap::Cylinder, Wrap::Torus].iter() {
+      let mut grid = recursive_backtracker::apply_to_with_rng(Grid::with_wrap(8, 9, *wrap), rng);
+      grid.braid_with_rng(0.5, rng);
+      // no weights, free steps, then every step dearer than 1
+      let (low, high) = [(1, 2), (0, 4), (2, 6)][round % 3];
+      for coords in grid.coords() {
+        grid.set_weight(&coords, rng.gen_range(low, high));
+      }
+      let start = GridCoords {
+        col_index: rng.gen_range(0, 9),
+        row_index: rng.gen_range(0, 8)
+      };
+      let goal = GridCoords {
+        col_index: rng.gen_range(0, 9),
+        row_index: rng.gen_range(0, 8)
+      };
+      let distances = solutions::dijkstra::Dijkstra::new(&grid, &start);
+      for heuristic in [solutions::astar::Heuristic::Manhattan, solutions::astar::Heuristic::Euclidean].iter() {
+        let route = solutions::astar::solve(&grid, &start, &goal, *heuristic);
+        assert_route(&grid, &route, &start, &goal);
+        assert_eq!(distances.distances.get(&goal), route.explored.get(&goal), "{:?} {:?}", wrap, heuristic);
+      }
+    }
+  }
+}
+
+#[test]
+fn astar_explores_less() {
+  let mut grid = Grid::new(20, 20);
+  grid.link_all();
+  let start = GridCoords {
+    col_index: 0,
+    row_index: 0
+  };
+  let goal = GridCoords {
+    col_index: 19,
+    row_index: 0
+  };
+  let route = solutions::astar::solve(&grid, &start, &goal, solutions::astar::Heuristic::Manhattan);
+  assert_route(&grid, &route, &start, &goal);
+  assert_eq!(20, route.path.len());
+  assert!(route.explored.len() < 400);
+
+  let mut walled = Grid::new(1, 2);
+  assert!(solutions::astar::solve(&walled, &start, &GridCoords {
+    col_index: 1,
+    row_index: 0
+  }, solutions::astar::Heuristic::Euclidean).path.is_empty());
+  walled.link_all();
+  assert_eq!(2, solutions::astar::solve(&walled, &start, &GridCoords {
+    col_index: 1,
+    row_index: 0
+  }, solutions::astar::Heuristic::Euclidean).path.len());
+}
+
+#[test]
+fn bidirectional_matches_dijkstra() {
+  let rng = &mut StdRng::seed_from_u64(21);
+  let mut grid = wilsons::apply_to_with_rng(Grid::new(20, 20), rng);
+  grid.braid_with_rng(0.5, rng);
+  let start = GridCoords {
+    col_index: 3,
+    row_index: 0
+  };
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &start);
+  let goals = [start, GridCoords {
+    col_index: 19,
+    row_index: 19
+  }, GridCoords {
+    col_index: 10,
+    row_index: 4
+  }];
+  for goal in goals.iter() {
+    let route = solutions::bidirectional::solve(&grid, &start, goal);
+    assert_route(&grid, &route, &start, goal);
+    assert_eq!(distances.distances[goal] as usize + 1, route.path.len());
+  }
+}
+
+#[test]
+fn astar_explored_gradation_test() {
+  let grid = recursive_backtracker::apply_to(Grid::new(30, 30));
+  let route = solutions::astar::solve(&grid, &GridCoords {
+    col_index: 0,
+    row_index: 0
+  }, &GridCoords {
+    col_index: 29,
+    row_index: 29
+  }, solutions::astar::Heuristic::Manhattan);
+  let grid_image = to_img(&grid, 10);
+  let grid_image = draw_distance_gradation(grid_image, route.max_distance, &route.explored, image::Rgb { data: [155, 155, 255] });
+  let grid_image = draw_solution(grid_image, &route.path);
+  grid_image.canvas.save("test-output/astar.png").unwrap();
+}
+
+#[test]
+fn svg_walls() {
+  let mut grid = Grid::new(1, 2);
+  let svg = grid.to_svg(10);
+  assert!(svg.starts_with("<svg"));
+  assert!(svg.trim_end().ends_with("</svg>"));
+  // 2 cells each with 4 walls, sharing 1
+  assert_eq!(7, svg.matches("<line").count());
+  grid.link_all();
+  assert_eq!(6, grid.to_svg(10).matches("<line").count());
+  assert!(svg.contains(r#"width="30" height="20""#));
+}
+
+#[test]
+fn svg_solution_and_gradation() {
+  let grid = recursive_backtracker::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(21));
+  let origin = GridCoords {
+    col_index: 0,
+    row_index: 0
+  };
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &origin);
+  let (farthest, _) = distances.farthest();
+  let solution = distances.shortest_path_to(&grid, &farthest);
+
+  let svg = grid.to_svg_with_solution(10, &solution);
+  assert_eq!(1, svg.matches("<polyline").count());
+  // row 0 is at the bottom, so the origin's center is near the bottom left
+  assert!(svg.contains(" 10,100\""));
+
+  let mut written = Vec::new();
+  grid.write_svg_with_solution(&mut written, 10, &solution).unwrap();
+  assert_eq!(svg, String::from_utf8(written).unwrap());
+
+  let grid_svg = grid_rs::grid::svg::to_svg(&grid, 10);
+  let grid_svg = grid_rs::grid::svg::draw_distance_gradation(grid_svg, distances.max_distance, &distances.distances, image::Rgb { data: [155, 255, 155] });
+  let grid_svg = grid_rs::grid::svg::draw_solution(grid_svg, &solution);
+  let svg = grid_svg.to_string();
+  assert_eq!(100, svg.matches("<rect x=").count());
+  std::fs::write("test-output/solution.svg", svg).unwrap();
+}
+
+#[test]
+fn svg_style() {
+  use grid_rs::grid::img::RenderStyle;
+  let grid = recursive_backtracker::apply_to_with_rng(Grid::new(2, 3), &mut StdRng::seed_from_u64(4));
+  let style = RenderStyle::new()
+    .with_wall_color(image::Rgb { data: [10, 20, 30] })
+    .with_wall_thickness(3)
+    .with_background(image::Rgb { data: [1, 2, 3] })
+    .with_padding(8)
+    .with_solution_color(image::Rgb { data: [200, 0, 0] })
+    .with_solution_width(2)
+    .with_start_marker(image::Rgb { data: [0, 0, 255] });
+  let svg = grid.to_svg_with_style(10, &style);
+  assert!(svg.contains(r#"width="46" height="36""#));
+  assert!(svg.contains(r#"fill="rgb(1,2,3)""#));
+  assert!(svg.contains(r#"stroke="rgb(10,20,30)" stroke-width="3""#));
+  let mut written = Vec::new();
+  grid.write_svg_with_style(&mut written, 10, &style).unwrap();
+  assert_eq!(svg, String::from_utf8(written).unwrap());
+
+  let solution = grid.coords();
+  let grid_svg = grid_rs::grid::svg::draw_solution(grid_rs::grid::svg::to_svg_with_style(&grid, 10, &style), &solution[..1]);
+  let svg = grid_svg.to_string();
+  assert!(svg.contains(r#"stroke="rgb(200,0,0)" stroke-width="2""#));
+  assert!(svg.contains(r#"width="5" height="5" fill="rgb(0,0,255)""#));
+  // the default matches what was drawn before there were styles
+  assert!(grid.to_svg(10).contains(r#"<g stroke="rgb(0,0,0)" stroke-width="1""#));
+}
+
+#[test]
+fn try_to_img_errors() {
+  let grid = recursive_backtracker::apply_to(Grid::new(3, 4));
+  match grid.try_to_img("test-output/maze.txt", 10) {
+    Err(GridError::UnsupportedExtension(extension)) => assert_eq!("txt", extension),
+    other => panic!("expected an unsupported extension, got {:?}", other.err())
+  }
+  assert!(!std::path::Path::new("test-output/maze.txt").exists());
+  match grid.try_to_img("test-output/no-such-dir/maze.png", 10) {
+    Err(GridError::Io(_)) => {}
+    other => panic!("expected an io error, got {:?}", other.err())
+  }
+  // the pixel count overflows long before the grid runs out of cells
+  match grid_rs::grid::img::try_to_img(&grid, u32::max_value()) {
+    Err(GridError::CanvasTooLarge { width, height, .. }) => assert_eq!((4, 3), (width, height)),
+    _ => panic!("expected the canvas to be too large")
+  }
+  let wide = Grid::new(1, 70000);
+  assert!(wide.write_png(Vec::new(), 70000).is_err());
+
+  let grid_image = grid.try_to_img("test-output/try.PNG", 10).unwrap();
+  assert_eq!((50, 40), grid_image.canvas.dimensions());
+}
+
+#[test]
+fn huge_padding_too_large() {
+  use grid_rs::grid::img::*;
+  // doubling the padding alone overflows
+  let style = || RenderStyle::new().with_padding(u32::max_value() / 2 + 1);
+  match try_to_img_with_style(&Grid::new(2, 2), 10, &style()) {
+    Err(GridError::CanvasTooLarge { width, height, .. }) => assert_eq!((2, 2), (width, height)),
+    _ => panic!("expected the canvas to be too large")
+  }
+  let renders: Vec<Box<dyn Fn() -> GridImage + std::panic::UnwindSafe>> = vec![
+    Box::new(move || polar_to_img_with_style(&PolarGrid::new(3), 10, &style())),
+    Box::new(move || hex_to_img_with_style(&HexGrid::new(2, 2), 10, &style())),
+    Box::new(move || triangle_to_img_with_style(&TriangleGrid::new(2, 2), 10, &style())),
+    Box::new(move || grid3d_to_img_with_style(&Grid3D::new(2, 2, 2), 10, &style())),
+    Box::new(move || weave_to_img_with_style(&WeaveGrid::new(2, 2), 10, 0.2, &style())),
+  ];
+  for render in renders {
+    let result = std::panic::catch_unwind(render);
+    let message = result.err().and_then(|err| err.downcast_ref::<String>().cloned()).unwrap();
+    assert!(message.ends_with("is too large to draw with 10px cells"), "{}", message);
+  }
+  let tries = vec![
+    try_polar_to_img_with_style(&PolarGrid::new(3), 10, &style()).err(),
+    try_hex_to_img_with_style(&HexGrid::new(2, 2), 10, &style()).err(),
+    try_triangle_to_img_with_style(&TriangleGrid::new(2, 2), 10, &style()).err(),
+    try_grid3d_to_img_with_style(&Grid3D::new(2, 2, 2), 10, &style()).err(),
+    try_weave_to_img_with_style(&WeaveGrid::new(2, 2), 10, 0.2, &style()).err(),
+  ];
+  for error in tries {
+    match error {
+      Some(GridError::CanvasTooLarge { cell_size, .. }) => assert_eq!(10, cell_size),
+      _ => panic!("expected the canvas to be too large")
+    }
+  }
+  match grid_rs::grid::svg::try_to_svg_with_style(&Grid::new(2, 2), 10, &style()) {
+    Err(GridError::CanvasTooLarge { width, height, .. }) => assert_eq!((2, 2), (width, height)),
+    _ => panic!("expected the svg to be too large")
+  }
+  assert!(grid_rs::grid::svg::try_to_svg(&Grid::new(1, 70000), 70000).is_err());
+}
+
+#[test]
+fn try_to_img_every_shape() {
+  use grid_rs::grid::img::GridImage;
+  let unsupported = |error: Result<GridImage, GridError>| match error {
+    Err(GridError::UnsupportedExtension(extension)) => assert_eq!("txt", extension),
+    _ => panic!("expected the extension to be refused")
+  };
+  unsupported(PolarGrid::new(3).try_to_img("test-output/polar.txt", 10));
+  unsupported(HexGrid::new(2, 2).try_to_img("test-output/hex.txt", 10));
+  unsupported(TriangleGrid::new(2, 2).try_to_img("test-output/triangle.txt", 10));
+  unsupported(Grid3D::new(2, 2, 2).try_to_img("test-output/grid3d.txt", 10));
+  unsupported(WeaveGrid::new(2, 2).try_to_img("test-output/weave.txt", 10, 0.2));
+  assert!(!std::path::Path::new("test-output/hex.txt").exists());
+
+  let grid_image = HexGrid::new(2, 2).try_to_img("test-output/try-hex.png", 10).unwrap();
+  let saved = image::open("test-output/try-hex.png").unwrap().to_rgb();
+  assert_eq!(grid_image.canvas.dimensions(), saved.dimensions());
+}
+
+#[test]
+fn write_png() {
+  let grid = recursive_backtracker::apply_to(make_grid());
+  let mut png = Vec::new();
+  grid.write_png(&mut png, 8).unwrap();
+  assert_eq!(&[0x89, b'P', b'N', b'G'], &png[..4]);
+  let decoded = image::load_from_memory(&png).unwrap().to_rgb();
+  assert_eq!((90, 90), decoded.dimensions());
+}
+
+#[test]
+fn render_style() {
+  use grid_rs::grid::img::{RenderStyle, to_img_with_style, hex_to_img_with_style};
+  let red = image::Rgb { data: [255, 0, 0] };
+  let navy = image::Rgb { data: [0, 0, 64] };
+  let cream = image::Rgb { data: [255, 250, 220] };
+  let style = RenderStyle::new()
+    .with_wall_color(navy)
+    .with_wall_thickness(3)
+    .with_background(cream)
+    .with_padding(10)
+    .with_solution_color(red)
+    .with_solution_width(2)
+    .with_start_marker(red)
+    .with_end_marker(navy);
+  assert_eq!(3, style.wall_thickness());
+  assert_eq!(RenderStyle::default(), RenderStyle::new());
+
+  let grid = Grid::new(2, 3);
+  let grid_image = to_img_with_style(&grid, 20, &style);
+  assert_eq!((80, 60), grid_image.canvas.dimensions());
+  assert_eq!(&cream, grid_image.canvas.get_pixel(0, 0));
+  // the outer wall along the left edge, 3 pixels wide
+  for x in 9..12 {
+    assert_eq!(&navy, grid_image.canvas.get_pixel(x, 20));
+  }
+  assert_eq!(&cream, grid_image.canvas.get_pixel(12, 20));
+
+  let mut open = Grid::new(1, 3);
+  open.link_all();
+  let solution = open.coords();
+  let grid_image = to_img_with_style(&open, 20, &style);
+  let grid_image = draw_solution(grid_image, &solution);
+  // markers cover the middle of the first and last cells, the line runs between them
+  assert_eq!(&red, grid_image.canvas.get_pixel(20, 20));
+  assert_eq!(&navy, grid_image.canvas.get_pixel(60, 20));
+  // 2 pixels wide
+  assert_eq!(&red, grid_image.canvas.get_pixel(40, 18));
+  assert_eq!(&red, grid_image.canvas.get_pixel(40, 19));
+  assert_eq!(&cream, grid_image.canvas.get_pixel(40, 20));
+
+  let hex = hex_to_img_with_style(&recursive_backtracker::apply_to(HexGrid::new(4, 4)), 20, &style);
+  assert_eq!(&cream, hex.canvas.get_pixel(0, 0));
+}
+
+#[test]
+fn laser_cut_style() {
+  let red = image::Rgb { data: [255, 0, 0] };
+  let blue = image::Rgb { data: [0, 0, 255] };
+  let black = image::Rgb { data: [0, 0, 0] };
+  let white = image::Rgb { data: [255, 255, 255] };
+  let style = grid_rs::grid::img::RenderStyle::new()
+    .with_wall_thickness(4)
+    .with_padding(8)
+    .with_start_marker(red)
+    .with_end_marker(blue);
+  let grid = recursive_backtracker::apply_to(Grid::new(12, 20));
+  let longest = solutions::dijkstra::Dijkstra::longest_path(&grid).unwrap();
+  let grid_image = grid_rs::grid::img::to_img_with_style(&grid, 24, &style);
+  let grid_image = draw_solution(grid_image, &longest.path);
+  grid_image.save("test-output/laser_cut.png").unwrap();
+  assert_eq!((496, 304), grid_image.canvas.dimensions());
+
+  // the outer wall along the left edge, 4 pixels wide, halfway up a cell
+  assert_eq!(&white, grid_image.canvas.get_pixel(6, 163));
+  for x in 7..11 {
+    assert_eq!(&black, grid_image.canvas.get_pixel(x, 163));
+  }
+  assert_eq!(&white, grid_image.canvas.get_pixel(11, 163));
+
+  // each marker is a square half a cell wide in the middle of its cell,
+  // image rows run top down
+  let corner = |coords: &GridCoords| (8 + coords.col_index as u32 * 24, 303 - 8 - coords.row_index as u32 * 24);
+  for (coords, marker) in [(&longest.start, red), (&longest.end, blue)].iter() {
+    let (x, y) = corner(coords);
+    assert_eq!(marker, grid_image.canvas.get_pixel(x + 12, y - 12));
+    assert_eq!(marker, grid_image.canvas.get_pixel(x + 6, y - 6));
+    assert_eq!(marker, grid_image.canvas.get_pixel(x + 17, y - 17));
+    assert_eq!(&white, grid_image.canvas.get_pixel(x + 5, y - 5));
+    assert_eq!(&white, grid_image.canvas.get_pixel(x + 18, y - 18));
+  }
+}
+
+// every generator on a `height` by `width` grid, seeded so failures repeat
+fn every_generator(height: usize, width: usize) -> Vec<(&'static str, Grid)> {
+  let rng = &mut StdRng::seed_from_u64((height * 1000 + width) as u64);
+  vec![
+    ("binary tree", binarytree::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("sidewinder", sidewinder::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("ellers", ellers::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("recursive division", recursive_division::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("aldous broder", aldous_broder::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("wilsons", wilsons::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("hybrid", hybrid::apply_to_with_rng(Grid::new(height, width), 0.5, rng)),
+    ("recursive backtracker", recursive_backtracker::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("hunt and kill", hunt_and_kill::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("kruskals", kruskals::apply_to_with_rng(Grid::new(height, width), rng)),
+    ("simplified prims", prims::apply_simplified_to_with_rng(Grid::new(height, width), rng)),
+    ("true prims", prims::apply_true_to_with_rng(Grid::new(height, width), rng)),
+    ("growing tree", growing_tree::apply_to_with_rng(Grid::new(height, width), growing_tree::Mix::default(), rng)),
+  ]
+}
+
+#[test]
+fn non_square_grids() {
+  let shapes = [(4, 15), (15, 4), (1, 12), (12, 1), (1, 1), (2, 7)];
+  for &(height, width) in shapes.iter() {
+    let rng = &mut StdRng::seed_from_u64(24);
+    let empty = Grid::new(height, width);
+    for _ in 0..50 {
+      let coords = empty.rand_cell_with_rng(rng).coords();
+      assert!(coords.row_index < height && coords.col_index < width);
+    }
+
+    for (name, grid) in every_generator(height, width) {
+      let shape = format!("{} on {}x{}", name, height, width);
+      assert_eq!(height * width, grid.coords().len(), "{}", shape);
+      let distances = solutions::dijkstra::Dijkstra::new(&grid, &grid.coords()[0]);
+      assert_eq!(height * width, distances.distances.len(), "{} isn't connected", shape);
+      assert_eq!(height * width - 1, link_count(&grid), "{} has loops", shape);
+      let longest = solutions::dijkstra::Dijkstra::longest_path(&grid).unwrap();
+      assert_eq!(longest.length as usize + 1, longest.path.len(), "{}", shape);
+      // a single row or column only has one way through
+      if height == 1 || width == 1 {
+        assert_eq!(height * width - 1, longest.length as usize, "{}", shape);
+      }
+
+      let text = format!("{}", grid);
+      let lines: Vec<&str> = text.lines().collect();
+      assert_eq!(height * 2 + 1, lines.len(), "{}", shape);
+      assert!(lines.iter().all(|line| line.len() == width * 4 + 1), "{}", shape);
+
+      let grid_image = to_img(&grid, 10);
+      assert_eq!((width as u32 * 10 + 10, height as u32 * 10 + 10), grid_image.canvas.dimensions(), "{}", shape);
+      // the outer wall runs along all four sides, drawn bottom up and then
+      // flipped so it's a pixel higher than the padding suggests
+      let (right, bottom) = (width as u32 * 10 + 5, height as u32 * 10 + 4);
+      for &(x, y) in [(5, 4), (right, 4), (5, bottom), (right, bottom)].iter() {
+        assert_eq!(&image::Rgb { data: [0, 0, 0] }, grid_image.canvas.get_pixel(x, y), "{}", shape);
+      }
+    }
+  }
+}
+
+#[test]
+fn non_square_to_image_test() {
+  let grid = recursive_backtracker::apply_to(Grid::new(6, 24));
+  let longest = solutions::dijkstra::Dijkstra::longest_path(&grid).unwrap();
+  let grid_image = grid.to_img_with_solution("test-output/wide.png", 12, &longest.path);
+  assert_eq!((298, 82), grid_image.canvas.dimensions());
+  let grid = wilsons::apply_to(Grid::new(24, 6));
+  let grid_image = grid.to_img("test-output/tall.png", 12);
+  assert_eq!((82, 298), grid_image.canvas.dimensions());
+}
+
+// a shape the library knows nothing about: a loop of cells, each next to
+// the cells either side of it
+struct Ring {
+  size: usize,
+  links: HashMap<usize, Vec<usize>>,
+}
+
+impl Ring {
+  fn new(size: usize) -> Ring {
+    Ring {
+      size,
+      links: HashMap::new(),
+    }
+  }
+}
+
+impl Topology for Ring {
+  type Coords = usize;
+
+  fn coords(&self) -> Vec<usize> {
+    (0..self.size).collect()
+  }
+
+  fn neighbor_coords(&self, coords: &usize) -> Vec<usize> {
+    vec![(coords + self.size - 1) % self.size, (coords + 1) % self.size]
+  }
+
+  fn linked_coords(&self, coords: &usize) -> Vec<usize> {
+    self.links.get(coords).cloned().unwrap_or_default()
+  }
+
+  fn link_bidi(&mut self, source: &usize, destination: &usize) {
+    self.links.entry(*source).or_default().push(*destination);
+    self.links.entry(*destination).or_default().push(*source);
+  }
+
+  fn unlink_bidi(&mut self, source: &usize, destination: &usize) {
+    self.links.entry(*source).or_default().retain(|linked| linked != destination);
+    self.links.entry(*destination).or_default().retain(|linked| linked != source);
+  }
+}
+
+#[test]
+fn custom_topology_mazes() {
+  let mazes = vec![
+    aldous_broder::apply_to(Ring::new(12)),
+    wilsons::apply_to(Ring::new(12)),
+    recursive_backtracker::apply_to(Ring::new(12)),
+    hunt_and_kill::apply_to(Ring::new(12)),
+    kruskals::apply_to(Ring::new(12)),
+    prims::apply_simplified_to(Ring::new(12)),
+    growing_tree::apply_to(Ring::new(12), growing_tree::Oldest),
+  ];
+  for grid in mazes {
+    assert_perfect(&grid);
+    // a ring with one gap is a single corridor
+    let longest = solutions::dijkstra::Dijkstra::longest_path(&grid).unwrap();
+    assert_eq!(11, longest.length);
+    assert!(!grid.is_linked(&longest.start, &longest.end));
+  }
+}
+
+#[test]
+fn seeded_wilsons_is_reproducible() {
+  let first = wilsons::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(42));
+  let second = wilsons::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(42));
+  assert_eq!(format!("{}", first), format!("{}", second));
+}
+
 #[ignore]
 #[test]
 fn to_image_test() {
@@ -147,6 +1610,26 @@ fn shortest_path() {
   println!("{:#?}", distances.shortest_path_to(&grid, &destination))
 }
 
+#[test]
+fn longest_path() {
+  let grid = binarytree::apply_to_with_rng(make_grid(), &mut StdRng::seed_from_u64(3));
+  let longest = solutions::dijkstra::Dijkstra::longest_path(&grid).unwrap();
+  assert_eq!(longest.length as usize + 1, longest.path.len());
+  assert_eq!(Some(&longest.start), longest.path.first());
+  assert_eq!(Some(&longest.end), longest.path.last());
+  // nothing in the maze is further from either end than the other end
+  let from_start = solutions::dijkstra::Dijkstra::new(&grid, &longest.start);
+  assert!(from_start.distances.values().all(|distance| *distance <= longest.length));
+  let from_end = solutions::dijkstra::Dijkstra::new(&grid, &longest.end);
+  assert!(from_end.distances.values().all(|distance| *distance <= longest.length));
+}
+
+#[test]
+fn longest_path_empty_grid() {
+  let grid = Grid::new(0, 0);
+  assert!(solutions::dijkstra::Dijkstra::longest_path(&grid).is_none());
+}
+
 #[test]
 fn to_image_with_solution_test() {
   let grid = make_grid();
@@ -178,3 +1661,90 @@ fn to_image_with_distance_gradation_test() {
   let grid_image = draw_distance_gradation(grid_image, distances.max_distance, &distances.distances, dark_green);
   grid_image.canvas.save("test-output/distance_gradation.png").unwrap();
 }
+
+// two rows of three, the bottom row open and a wall partway along the top
+fn text_grid() -> Grid {
+  let mut grid = Grid::new(2, 3);
+  let at = |row_index, col_index| GridCoords { col_index, row_index };
+  grid.link_bidi(&at(0, 0), &at(0, 1));
+  grid.link_bidi(&at(0, 1), &at(0, 2));
+  grid.link_bidi(&at(0, 1), &at(1, 1));
+  grid.link_bidi(&at(1, 0), &at(1, 1));
+  grid.link_bidi(&at(1, 2), &at(0, 2));
+  grid
+}
+
+#[test]
+fn box_drawing() {
+  let grid = text_grid();
+  let expected = "┌───────┬───┐\n│       │   │\n├───╴   ╵   │\n│           │\n└───────────┘\n";
+  assert_eq!(expected, grid.to_box_drawing());
+  // same lines as the ascii drawing
+  assert_eq!(format!("{}", grid).lines().count(), grid.to_box_drawing().lines().count());
+}
+
+#[test]
+fn box_drawing_overlays() {
+  let grid = text_grid();
+  let at = |row_index, col_index| GridCoords { col_index, row_index };
+  let distances = solutions::dijkstra::Dijkstra::new(&grid, &at(1, 0));
+  let expected = "┌───────┬───┐\n│ 0   1 │ 4 │\n├───╴   ╵   │\n│ 3   2   3 │\n└───────────┘\n";
+  assert_eq!(expected, grid.to_box_drawing_with_distances(&distances.distances));
+
+  let path = [at(1, 0), at(1, 1), at(0, 1)];
+  let expected = "┌───────┬───┐\n│ •   • │   │\n├───╴   ╵   │\n│     •     │\n└───────────┘\n";
+  assert_eq!(expected, grid.to_box_drawing_with_path(&path));
+
+  // base 36, and too far to fit
+  let mut far = HashMap::new();
+  far.insert(at(1, 0), 35);
+  far.insert(at(1, 1), 36 * 36);
+  far.insert(at(1, 2), 36 * 36 * 36);
+  let expected = "┌───────┬───┐\n│ z  100│###│\n├───╴   ╵   │\n│           │\n└───────────┘\n";
+  assert_eq!(expected, grid.to_box_drawing_with_distances(&far));
+}
+
+#[test]
+fn half_blocks() {
+  let grid = text_grid();
+  assert_eq!("▗▄▄▄\n▐▄▐▐\n▐▄▄▟\n", grid.to_half_blocks());
+  // one character a cell plus the top and left edges
+  let grid = recursive_backtracker::apply_to(Grid::new(7, 4));
+  let text = grid.to_half_blocks();
+  assert_eq!(8, text.lines().count());
+  assert!(text.lines().all(|line| line.chars().count() == 5));
+}
+
+#[test]
+fn text_empty_grids() {
+  // a flat line of wall, with no rows to put cells in
+  let no_rows = Grid::new(0, 5);
+  assert_eq!("╶───────────────────╴\n", no_rows.to_box_drawing());
+  assert_eq!("▗▄▄▄▄▄\n", no_rows.to_half_blocks());
+  let no_cols = Grid::new(3, 0);
+  assert_eq!("╷\n│\n│\n│\n│\n│\n╵\n", no_cols.to_box_drawing());
+  assert_eq!("▗\n▐\n▐\n▐\n", no_cols.to_half_blocks());
+  assert_eq!(" \n", Grid::new(0, 0).to_box_drawing());
+}
+
+#[test]
+fn text_masked_and_wrapped() {
+  let at = |row_index, col_index| GridCoords { col_index, row_index };
+  let mut mask = Mask::new(2, 3);
+  mask.set(&at(1, 2), false);
+  let mut grid = Grid::from_mask(&mask);
+  grid.link_bidi(&at(0, 0), &at(0, 1));
+  grid.link_bidi(&at(0, 1), &at(0, 2));
+  grid.link_bidi(&at(0, 0), &at(1, 0));
+  grid.link_bidi(&at(0, 1), &at(1, 1));
+  let expected = "┌───┬───┐    \n│   │   │    \n│   ╵   └───┐\n│           │\n└───────────┘\n";
+  assert_eq!(expected, grid.to_box_drawing());
+  // the masked cell is filled in
+  assert_eq!("▗▄▄ \n▐▐▐▙\n▐▄▄▟\n", grid.to_half_blocks());
+
+  let mut wrapped = Grid::with_wrap(2, 3, Wrap::Cylinder);
+  wrapped.link_all();
+  let expected = "╶───────────╴\n             \n             \n             \n╶───────────╴\n";
+  assert_eq!(expected, wrapped.to_box_drawing());
+  assert_eq!("▗▄▄▄\n    \n▗▄▄▄\n", wrapped.to_half_blocks());
+}